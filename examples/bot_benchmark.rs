@@ -0,0 +1,81 @@
+// Plays seeded games without a window, one bot alone on the board, and
+// prints how many tries every bot level needs on average.
+//
+//     cargo run --release --example bot_benchmark -- 200
+
+use std::env;
+use std::time::Duration;
+
+use memory_game::bot::{Bot, BotLevel};
+use memory_game::engine::{Engine, GameEvent, Outcome, Rules};
+
+const COLUMNS: u32 = 6;
+const ROWS: u32 = 4;
+const STEP: Duration = Duration::from_millis(100);
+
+struct Game {
+    // Pairs turned over, matched or not
+    moves: usize,
+    played: Duration,
+}
+
+fn play(level: BotLevel, seed: u64) -> Game {
+    let rules = Rules {
+        duration: Duration::from_secs(3600),
+        bonuses: Vec::new(),
+        ..Rules::default()
+    };
+    let mut engine = Engine::new(COLUMNS, ROWS, rules, seed);
+    let mut bot = Bot::new(level.model(), seed);
+    let mut moves = 0;
+
+    while engine.outcome() == Outcome::Playing {
+        if let Some(command) = bot.think(&engine) {
+            engine.apply(command);
+        }
+        engine.tick(STEP);
+
+        for event in engine.drain_events() {
+            if matches!(event, GameEvent::Matched(..) | GameEvent::NotMatched(..)) {
+                moves += 1;
+            }
+            bot.observe(&event);
+        }
+    }
+
+    Game {
+        moves,
+        played: engine.played(),
+    }
+}
+
+fn main() {
+    let games: u64 = match env::args().nth(1).map(|games| games.parse()) {
+        None => 100,
+        Some(Ok(games)) if games > 0 => games,
+        Some(_) => {
+            eprintln!("usage: bot_benchmark [games]");
+            std::process::exit(2);
+        }
+    };
+
+    println!(
+        "{} games per level on a {}x{} board ({} pairs)",
+        games,
+        COLUMNS,
+        ROWS,
+        COLUMNS * ROWS / 2
+    );
+    for level in BotLevel::ALL {
+        let results: Vec<Game> = (0..games).map(|seed| play(level, seed)).collect();
+        let moves: usize = results.iter().map(|result| result.moves).sum();
+        let played: Duration = results.iter().map(|result| result.played).sum();
+
+        println!(
+            "{:<12} {:>7.1} moves  {:>6.1} s",
+            level.name(),
+            moves as f64 / games as f64,
+            played.as_secs_f64() / games as f64
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ggez::graphics;
+use ggez::{Context, GameResult};
+
+use crate::atlas::Atlas;
+use memory_game::deck::Deck;
+
+// Time a frame may spend on images that are not needed yet
+const PRELOAD_BUDGET: Duration = Duration::from_millis(4);
+
+enum Preload {
+    Image(PathBuf),
+    // Comes after the images of the deck
+    Atlas(Deck),
+}
+
+// Every image is decoded once and then shared. A `graphics::Image` is only
+// a handle, so handing out clones is cheap.
+#[derive(Default)]
+pub struct Assets {
+    images: HashMap<PathBuf, graphics::Image>,
+    // By deck directory
+    atlases: HashMap<String, Atlas>,
+    // Atlases that still wait for a frame to be drawn in
+    unpainted: Vec<Atlas>,
+    queue: VecDeque<Preload>,
+}
+
+impl Assets {
+    pub fn image(&mut self, ctx: &mut Context, path: &Path) -> GameResult<graphics::Image> {
+        if let Some(image) = self.images.get(path) {
+            return Ok(image.clone());
+        }
+
+        let image = graphics::Image::from_path(ctx, path)?;
+        self.images.insert(path.to_owned(), image.clone());
+
+        Ok(image)
+    }
+
+    pub fn atlas(&mut self, ctx: &mut Context, deck: &Deck) -> GameResult<Atlas> {
+        if let Some(atlas) = self.atlases.get(&deck.dir) {
+            return Ok(atlas.clone());
+        }
+
+        let atlas = Atlas::new(ctx, self, deck)?;
+        self.atlases.insert(deck.dir.clone(), atlas.clone());
+        self.unpainted.push(atlas.clone());
+
+        Ok(atlas)
+    }
+
+    // Has to come before anything is drawn with the new atlases
+    pub fn paint(&mut self, ctx: &mut Context) -> GameResult {
+        for atlas in self.unpainted.drain(..) {
+            atlas.paint(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    // Queues the images and the atlas of a deck for `preload`
+    pub fn queue_deck(&mut self, deck: &Deck) {
+        if self.atlases.contains_key(&deck.dir) {
+            return;
+        }
+
+        let paths = std::iter::once(deck.back()).chain(deck.faces());
+        for path in paths {
+            if !self.images.contains_key(path) {
+                self.queue.push_back(Preload::Image(path.to_owned()));
+            }
+        }
+        self.queue.push_back(Preload::Atlas(deck.clone()));
+    }
+
+    // Loads queued images for a few milliseconds, so that starting a game
+    // finds them ready without the frame rate noticing
+    pub fn preload(&mut self, ctx: &mut Context) {
+        let start = Instant::now();
+
+        while let Some(preload) = self.queue.pop_front() {
+            // A broken image shows up as an error once a game needs it
+            let result = match &preload {
+                Preload::Image(path) => self
+                    .image(ctx, path)
+                    .map(|_| ())
+                    .map_err(|e| format!("{}: {}", path.display(), e)),
+                Preload::Atlas(deck) => self
+                    .atlas(ctx, deck)
+                    .map(|_| ())
+                    .map_err(|e| format!("the deck {}: {}", deck.dir, e)),
+            };
+            if let Err(e) = result {
+                eprintln!("Could not preload {}", e);
+            }
+
+            if start.elapsed() >= PRELOAD_BUDGET {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use ggez::graphics;
+use ggez::graphics::{Color, DrawParam, InstanceArray, Rect};
+use ggez::mint::{Point2, Vector2};
+use ggez::{Context, GameResult};
+
+use crate::assets::Assets;
+use crate::card::Card;
+use crate::tween::CardAnimation;
+use memory_game::deck::Deck;
+
+// Empty pixels around every image, so that filtering does not pick up the
+// edge of the neighbouring one
+const PADDING: f32 = 2.0;
+// Larger decks get smaller cells instead of a texture the GPU may refuse
+const MAX_SIZE: f32 = 4096.0;
+
+// The back and all the faces of a deck in one image, each in a cell of the
+// same size. Drawing only works during a frame, so the images are copied in
+// by `paint` from the draw that comes next.
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    image: graphics::Image,
+    // Back first, then the faces, in the order of `cells`
+    sources: Vec<graphics::Image>,
+    cell: Vector2<f32>,
+    cells: Vec<Rect>,
+}
+
+impl Atlas {
+    pub fn new(ctx: &mut Context, assets: &mut Assets, deck: &Deck) -> GameResult<Atlas> {
+        let mut sources = vec![assets.image(ctx, deck.back())?];
+        for path in deck.faces() {
+            sources.push(assets.image(ctx, path)?);
+        }
+
+        // Cells fit the largest image, stretching the smaller ones like a
+        // card would
+        let mut cell = Vector2 {
+            x: sources.iter().map(|image| image.width()).max().unwrap_or(1) as f32,
+            y: sources
+                .iter()
+                .map(|image| image.height())
+                .max()
+                .unwrap_or(1) as f32,
+        };
+        let columns = (sources.len() as f32).sqrt().ceil();
+        let rows = (sources.len() as f32 / columns).ceil();
+
+        let fit = (MAX_SIZE / (columns * (cell.x + 2. * PADDING)))
+            .min(MAX_SIZE / (rows * (cell.y + 2. * PADDING)))
+            .min(1.0);
+        cell.x = (cell.x * fit).floor();
+        cell.y = (cell.y * fit).floor();
+
+        let width = columns * (cell.x + 2. * PADDING);
+        let height = rows * (cell.y + 2. * PADDING);
+        let image = graphics::Image::new_canvas_image(
+            ctx,
+            ctx.gfx.surface_format(),
+            width as u32,
+            height as u32,
+            1,
+        );
+
+        // Texture coordinates of every cell
+        let cells = (0..sources.len())
+            .map(|index| {
+                let x = (index as f32 % columns) * (cell.x + 2. * PADDING) + PADDING;
+                let y = (index as f32 / columns).floor() * (cell.y + 2. * PADDING) + PADDING;
+
+                Rect::new(x / width, y / height, cell.x / width, cell.y / height)
+            })
+            .collect();
+
+        Ok(Atlas {
+            image,
+            sources,
+            cell,
+            cells,
+        })
+    }
+
+    pub fn paint(&self, ctx: &mut Context) -> GameResult {
+        let width = self.image.width() as f32;
+        let height = self.image.height() as f32;
+
+        let mut canvas =
+            graphics::Canvas::from_image(ctx, self.image.clone(), Color::from_rgba(0, 0, 0, 0));
+        for (source, cell) in self.sources.iter().zip(self.cells.iter()) {
+            canvas.draw(
+                source,
+                DrawParam::default()
+                    .dest(Point2 {
+                        x: cell.x * width,
+                        y: cell.y * height,
+                    })
+                    .scale(Vector2 {
+                        x: self.cell.x / source.width() as f32,
+                        y: self.cell.y / source.height() as f32,
+                    }),
+            );
+        }
+
+        canvas.finish(ctx)
+    }
+
+    pub fn back(&self) -> Rect {
+        self.cells[0]
+    }
+
+    // Match id `n` is the `n`th face
+    pub fn face(&self, match_id: u32) -> Rect {
+        self.cells
+            .get(match_id as usize)
+            .filter(|_| match_id > 0)
+            .copied()
+            .unwrap_or(self.cells[0])
+    }
+
+    // Scale that stretches a cell over the given size
+    pub fn scale(&self, size: Vector2<f32>) -> Vector2<f32> {
+        Vector2 {
+            x: size.x / self.cell.x,
+            y: size.y / self.cell.y,
+        }
+    }
+}
+
+// The whole board in a single draw call
+pub struct CardBatch {
+    atlas: Atlas,
+    instances: InstanceArray,
+}
+
+impl CardBatch {
+    pub fn new(ctx: &mut Context, atlas: Atlas) -> CardBatch {
+        let instances = InstanceArray::new(ctx, atlas.image.clone());
+
+        CardBatch { atlas, instances }
+    }
+
+    pub fn draw<'a>(
+        &mut self,
+        canvas: &mut graphics::Canvas,
+        cards: impl Iterator<Item = &'a Card>,
+        animation: &CardAnimation,
+    ) {
+        // Cards on their way to the score fly over the others
+        let mut cards: Vec<&Card> = cards.collect();
+        cards.sort_by_key(|card| card.is_dismissed());
+
+        self.instances.set(
+            cards
+                .iter()
+                .filter_map(|card| card.instance(&self.atlas, animation)),
+        );
+        canvas.draw(&self.instances, DrawParam::default());
+
+        // Labels are text and come on top
+        for card in cards {
+            card.draw_label(canvas);
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use ggez::audio::SoundSource;
+use ggez::{Context, GameResult};
+
+use crate::music::{Mood, Music};
+use crate::settings::Settings;
+use crate::sounds::Sounds;
+
+// What the game has to say, the sound that goes with it comes from the
+// sound pack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+    LevelStart,
+    Match,
+    Mismatch,
+    BonusActivated,
+    Win,
+    Lose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundCategory {
+    Effects,
+    Music,
+}
+
+// What happens when a sound is asked for while it is still playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overlap {
+    // Cut it off and start again
+    Restart,
+    // Play another one on top
+    Layer,
+    // Let the one that plays finish
+    Skip,
+}
+
+impl SoundEvent {
+    pub const ALL: [SoundEvent; 6] = [
+        SoundEvent::LevelStart,
+        SoundEvent::Match,
+        SoundEvent::Mismatch,
+        SoundEvent::BonusActivated,
+        SoundEvent::Win,
+        SoundEvent::Lose,
+    ];
+
+    // Name in the manifest of a sound pack
+    pub fn key(self) -> &'static str {
+        match self {
+            SoundEvent::LevelStart => "level_start",
+            SoundEvent::Match => "match",
+            SoundEvent::Mismatch => "mismatch",
+            SoundEvent::BonusActivated => "bonus_activated",
+            SoundEvent::Win => "win",
+            SoundEvent::Lose => "lose",
+        }
+    }
+
+    fn overlap(self) -> Overlap {
+        match self {
+            // Quick matches in a row each get heard
+            SoundEvent::Match => Overlap::Layer,
+            SoundEvent::BonusActivated => Overlap::Skip,
+            SoundEvent::LevelStart | SoundEvent::Mismatch | SoundEvent::Win | SoundEvent::Lose => {
+                Overlap::Restart
+            }
+        }
+    }
+}
+
+// Plays the sounds and the music of the game. Events are collected during
+// a frame and played together by `flush`, so the same event only sounds
+// once per frame however often it came up.
+pub struct Audio {
+    sounds: Sounds,
+    pending: Vec<SoundEvent>,
+    music: Music,
+    music_on: bool,
+    // All from 0 to 1
+    master_volume: f32,
+    effects_volume: f32,
+    music_volume: f32,
+    muted: bool,
+}
+
+impl Audio {
+    pub fn new(sounds: Sounds, music: Music) -> Audio {
+        Audio {
+            sounds,
+            pending: Vec::new(),
+            music,
+            music_on: true,
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            music_volume: 1.0,
+            muted: false,
+        }
+    }
+
+    // For another sound pack
+    pub fn set_sounds(&mut self, sounds: Sounds) {
+        self.sounds = sounds;
+    }
+
+    pub fn apply(&mut self, settings: &Settings) {
+        self.master_volume = settings.master_volume as f32 / 100.;
+        self.effects_volume = settings.sfx_volume as f32 / 100.;
+        self.music_volume = settings.music_volume as f32 / 100.;
+        self.muted = settings.muted;
+        self.music_on = settings.music;
+    }
+
+    // How loud a category plays in the end
+    pub fn gain(&self, category: SoundCategory) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+
+        let volume = match category {
+            SoundCategory::Effects => self.effects_volume,
+            SoundCategory::Music => self.music_volume,
+        };
+
+        self.master_volume * volume
+    }
+
+    // Music for the screen that shows, unless it is turned off
+    pub fn set_mood(&mut self, mood: Mood) {
+        self.music.set_mood(Some(mood).filter(|_| self.music_on));
+    }
+
+    pub fn play(&mut self, event: SoundEvent) {
+        if !self.pending.contains(&event) {
+            self.pending.push(event);
+        }
+    }
+
+    // Once a frame, after everything that can make a sound or change the
+    // music
+    pub fn flush(&mut self, ctx: &mut Context) -> GameResult {
+        for event in std::mem::take(&mut self.pending) {
+            let gain = self.gain(SoundCategory::Effects);
+            if gain <= 0.0 {
+                continue;
+            }
+
+            let source = match self.sounds.source(event) {
+                Some(source) => source,
+                None => continue,
+            };
+            // A detached sound keeps its volume, the next one starts at full
+            source.set_volume(gain);
+            match event.overlap() {
+                Overlap::Restart => source.play(ctx)?,
+                Overlap::Layer => source.play_detached(ctx)?,
+                Overlap::Skip if source.playing() => {}
+                Overlap::Skip => source.play(ctx)?,
+            }
+        }
+
+        let gain = self.gain(SoundCategory::Music);
+        self.music.update(ctx, gain);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_sound_plays_once_per_frame() {
+        let mut audio = Audio::new(Sounds::default(), Music::default());
+
+        audio.play(SoundEvent::Match);
+        audio.play(SoundEvent::Match);
+        audio.play(SoundEvent::Win);
+        audio.play(SoundEvent::Match);
+        assert_eq!(audio.pending, vec![SoundEvent::Match, SoundEvent::Win]);
+
+        // Played by flush, the next frame hears it again
+        audio.pending.clear();
+        audio.play(SoundEvent::Match);
+        assert_eq!(audio.pending, vec![SoundEvent::Match]);
+    }
+}
@@ -0,0 +1,141 @@
+// Server for network games, the game itself is in `memory_game::server`.
+//
+//     cargo run --bin server -- --players 2 --port 7878
+//
+// and then `cargo run -- --connect <host>:7878` once for every player.
+
+use std::env;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use memory_game::deck;
+use memory_game::deck::Deck;
+use memory_game::engine::Engine;
+use memory_game::net;
+use memory_game::server;
+
+struct Options {
+    port: u16,
+    // Where the decks are, to check the board fits the deck
+    resources: PathBuf,
+    game: server::Options,
+}
+
+// Reads `--port`, `--players`, `--columns`, `--rows`, `--seconds`, `--seed`,
+// `--deck`, `--resources` (all `--flag <value>` or `--flag=value`) and
+// `--race`
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        port: net::DEFAULT_PORT,
+        resources: default_resources(),
+        game: server::Options::default(),
+    };
+
+    // Skip the program name
+    args.next();
+
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+            None => (arg, None),
+        };
+
+        if flag == "--race" {
+            options.game.race = true;
+            continue;
+        }
+
+        let value = match value {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| format!("{} expects a value", flag))?,
+        };
+        let invalid = |_| format!("invalid value for {}: {}", flag, value);
+
+        match flag.as_str() {
+            "--port" => options.port = value.parse().map_err(invalid)?,
+            "--players" => options.game.players = value.parse().map_err(invalid)?,
+            "--columns" => options.game.columns = value.parse().map_err(invalid)?,
+            "--rows" => options.game.rows = value.parse().map_err(invalid)?,
+            "--seconds" => options.game.seconds = value.parse().map_err(invalid)?,
+            "--seed" => options.game.seed = Some(value.parse().map_err(invalid)?),
+            "--deck" => options.game.deck = value,
+            "--resources" => options.resources = PathBuf::from(value),
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    let game = &options.game;
+    if game.players == 0 {
+        return Err("there has to be at least one player".to_owned());
+    }
+    let cells = game
+        .columns
+        .checked_mul(game.rows)
+        .ok_or_else(|| "the board is too big".to_owned())?;
+    if cells < 2 {
+        return Err("the board needs room for at least one pair".to_owned());
+    }
+
+    Ok(options)
+}
+
+// Next to the sources when run through cargo, like the game does
+fn default_resources() -> PathBuf {
+    match env::var("CARGO_MANIFEST_DIR") {
+        Ok(manifest_dir) => Path::new(&manifest_dir).join("resources"),
+        Err(_) => PathBuf::from("resources"),
+    }
+}
+
+// The clients draw the board with this deck, so it has to exist and have a
+// face for every pair
+fn check_deck(options: &Options) -> Result<(), String> {
+    let game = &options.game;
+    let path = options.resources.join(&game.deck).join(deck::MANIFEST);
+    let source = fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "There is no deck \"{}\": {}: {}",
+            game.deck,
+            path.display(),
+            e
+        )
+    })?;
+    let deck = Deck::parse(&game.deck, &source)?;
+
+    deck.check(Engine::cells(game.columns, game.rows).len() / 2)
+        .map_err(|e| e.to_string())
+}
+
+fn main() {
+    let options = match parse_args(env::args()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+
+    if let Err(message) = check_deck(&options) {
+        eprintln!("{}", message);
+        process::exit(2);
+    }
+
+    if let Err(e) = run(&options) {
+        eprintln!("Server stopped: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(options: &Options) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", options.port))?;
+    println!(
+        "Waiting for {} players on port {}",
+        options.game.players, options.port
+    );
+
+    server::serve(&listener, &options.game)
+}
@@ -1,76 +1,32 @@
-use std::time::Duration;
-
 use ggez::graphics;
-use ggez::graphics::{Color, DrawParam, Quad, TextLayout};
-use ggez::{Context, GameResult};
+use ggez::graphics::Color;
+use ggez::GameResult;
 
-#[derive(Debug)]
-pub enum BonusState {
-    Used,
-    NotUsed,
-    Using,
-    NotActive,
-}
+use crate::language::Language;
+use crate::menu_button::MenuButton;
+
+pub use memory_game::engine::BonusState;
+use memory_game::engine::{BonusKind, Engine};
 
 #[derive(Debug)]
 pub struct Bonus {
-    text: graphics::Text,
+    button: MenuButton,
     pub state: BonusState,
-    width: f32,
-    height: f32,
-    start_x: f32,
-    start_y: f32,
-    pub started: Option<Duration>,
-    pub duration: Duration,
 }
 
 impl Bonus {
     pub fn new(
-        _ctx: &mut Context,
         text: String,
         state: BonusState,
         width: f32,
         height: f32,
         start_x: f32,
         start_y: f32,
-        duration: Duration,
-    ) -> GameResult<Bonus> {
-        let mut bonus_text = graphics::Text::new(text);
-        bonus_text
-            .set_scale(30.)
-            .set_layout(TextLayout {
-                h_align: graphics::TextAlign::Middle,
-                v_align: graphics::TextAlign::Middle,
-            })
-            .set_bounds([start_x + (width / 2.), start_y + (height / 2.)])
-            .set_wrap(true);
-
-        Ok(Bonus {
-            text:bonus_text,
+    ) -> Bonus {
+        Bonus {
+            button: MenuButton::new(text, width, height, start_x, start_y),
             state,
-            width,
-            height,
-            start_x,
-            start_y,
-            duration,
-            started: None,
-        })
-    }
-    pub fn update(&mut self, ctx: &mut Context) -> GameResult {
-        // Set the time that started using 
-        if matches!(self.state, BonusState::Using) && self.started.is_none() {
-            self.started = Some(ctx.time.time_since_start())
-
-        } else if matches!(self.state, BonusState::Using) && !self.started.is_none() {
-            if let Some(start) = self.started {
-                // if the given duration has passed update the state
-                if start + self.duration < ctx.time.time_since_start() {
-                    self.update_state()?;
-                }
-            }
         }
-
-        Ok(())
     }
 
     pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
@@ -79,60 +35,15 @@ impl Bonus {
             BonusState::NotUsed => Color::GREEN,
             BonusState::Using => Color::YELLOW,
             BonusState::NotActive => return Ok(()),
+            BonusState::Disabled => return Ok(()),
         };
 
-        canvas.draw(
-            &self.text,
-            DrawParam::default()
-                .color(Color::WHITE)
-                .dest([
-                    self.start_x + (self.width / 2.),
-                    self.start_y + (self.height / 2.),
-                ])
-                .z(5),
-        );
-        canvas.draw(
-            &Quad,
-            DrawParam::default()
-                .color(rect_color)
-                .scale([self.width, self.height])
-                .dest([
-                    self.start_x + (self.width / 2.),
-                    self.start_y + (self.height / 2.),
-                ])
-                .offset([0.5, 0.5]),
-        );
-
-        Ok(())
+        self.button.draw_in(canvas, rect_color)
     }
 
-    pub fn update_state(&mut self) -> GameResult {
-        self.state = match self.state {
-            BonusState::Used => BonusState::Used,
-            BonusState::NotUsed => BonusState::Using,
-            BonusState::Using => BonusState::Used,
-            BonusState::NotActive => BonusState::NotUsed,
-        };
-        Ok(())
-    }
-
-    pub fn click_and_update(&mut self, x: f32, y: f32) -> bool {
-        if y < self.start_y || y > self.start_y + self.height {
-            return false;
-        }
-        if x < self.start_x || x > self.start_x + self.width {
-            return false;
-        }
-
-        return match self.state {
-            BonusState::Used => false,
-            BonusState::NotUsed => {
-                self.update_state();
-                return true;
-            }
-            BonusState::Using => false,
-            BonusState::NotActive => false,
-        };
+    // Only a bonus that is ready to be used can be clicked
+    pub fn is_clicked(&self, x: f32, y: f32) -> bool {
+        self.button.is_clicked(x, y) && self.state == BonusState::NotUsed
     }
 }
 
@@ -144,54 +55,61 @@ pub struct Bonuses {
 }
 
 impl Bonuses {
-    pub fn new(ctx: &mut Context, screen_width: f32) -> GameResult<Bonuses> {
+    pub fn new(language: Language, screen_width: f32, start_y: f32) -> Bonuses {
         let start_x = (screen_width - (600. + 100.)) / 2.;
         let bonus_time = Bonus::new(
-            ctx,
-            "+15 sec".to_owned(),
+            language.tr("+15 sec").to_owned(),
             BonusState::NotActive,
             200.0,
             70.,
             start_x,
-            800.,
-            Duration::new(2, 0),
-        )?;
+            start_y,
+        );
         let freeze_time = Bonus::new(
-            ctx,
-            "Freeze time".to_owned(),
+            language.tr("Freeze time").to_owned(),
             BonusState::NotUsed,
             200.0,
             70.,
             start_x + 250.,
-            800.,
-            Duration::new(15, 0),
-        )?;
+            start_y,
+        );
         let free_match = Bonus::new(
-            ctx,
-            "Match hint".to_owned(),
+            language.tr("Match hint").to_owned(),
             BonusState::NotUsed,
             200.0,
             70.,
             start_x + 500.,
-            800.,
-            Duration::new(1, 0),
-        )?;
+            start_y,
+        );
 
-        Ok(Bonuses {
+        Bonuses {
             bonus_time,
             freeze_time,
             free_match,
-        })
+        }
     }
 
-    pub fn update(&mut self, ctx: &mut Context) -> GameResult {
-        self.bonus_time.update(ctx)?;
-        self.free_match.update(ctx)?;
-        self.freeze_time.update(ctx)?;
+    // Mirror the bonus states kept by the rules engine
+    pub fn update(&mut self, engine: &Engine) -> GameResult {
+        self.bonus_time.state = engine.bonus(BonusKind::BonusTime).state;
+        self.freeze_time.state = engine.bonus(BonusKind::FreezeTime).state;
+        self.free_match.state = engine.bonus(BonusKind::FreeMatch).state;
 
         Ok(())
     }
 
+    pub fn clicked(&self, x: f32, y: f32) -> Option<BonusKind> {
+        if self.bonus_time.is_clicked(x, y) {
+            Some(BonusKind::BonusTime)
+        } else if self.freeze_time.is_clicked(x, y) {
+            Some(BonusKind::FreezeTime)
+        } else if self.free_match.is_clicked(x, y) {
+            Some(BonusKind::FreeMatch)
+        } else {
+            None
+        }
+    }
+
     pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
         self.bonus_time.draw(canvas)?;
         self.free_match.draw(canvas)?;
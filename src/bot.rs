@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::engine::{Command, Engine, GameEvent, Position};
+
+// How good a bot is at remembering cards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryModel {
+    // Cards remembered at once, the oldest ones go first
+    pub capacity: usize,
+    // Chance for each remembered card to slip whenever a new card is shown
+    pub forget_chance: f64,
+    // Game time the bot thinks before every flip
+    pub reaction: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotLevel {
+    Random,
+    Easy,
+    Medium,
+    Hard,
+    Perfect,
+}
+
+impl BotLevel {
+    pub const ALL: [BotLevel; 5] = [
+        BotLevel::Random,
+        BotLevel::Easy,
+        BotLevel::Medium,
+        BotLevel::Hard,
+        BotLevel::Perfect,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BotLevel::Random => "Random bot",
+            BotLevel::Easy => "Easy bot",
+            BotLevel::Medium => "Medium bot",
+            BotLevel::Hard => "Hard bot",
+            BotLevel::Perfect => "Perfect bot",
+        }
+    }
+
+    // The random bot remembers nothing and the perfect one everything
+    pub fn model(self) -> MemoryModel {
+        let (capacity, forget_chance, reaction) = match self {
+            BotLevel::Random => (0, 0.0, 800),
+            BotLevel::Easy => (4, 0.25, 1500),
+            BotLevel::Medium => (8, 0.1, 1000),
+            BotLevel::Hard => (16, 0.02, 700),
+            BotLevel::Perfect => (usize::MAX, 0.0, 500),
+        };
+
+        MemoryModel {
+            capacity,
+            forget_chance,
+            reaction: Duration::from_millis(reaction),
+        }
+    }
+}
+
+// A computer player. It only learns match ids from cards that are shown to
+// everyone and plays with the same commands as a click.
+pub struct Bot {
+    model: MemoryModel,
+    // Oldest first
+    memory: Vec<(Position, u32)>,
+    rng: ChaCha8Rng,
+    thinking_since: Option<Duration>,
+}
+
+impl Bot {
+    pub fn new(model: MemoryModel, seed: u64) -> Bot {
+        Bot {
+            model,
+            memory: Vec::new(),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            thinking_since: None,
+        }
+    }
+
+    // Every bot sees every event, no matter whose turn it is
+    pub fn observe(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::CardFlipped(position, match_id) => {
+                let forget_chance = self.model.forget_chance;
+                let rng = &mut self.rng;
+                self.memory.retain(|&(known, _)| {
+                    known != position && !rng.gen_bool(forget_chance.clamp(0.0, 1.0))
+                });
+
+                if self.model.capacity > 0 {
+                    if self.memory.len() >= self.model.capacity {
+                        self.memory.remove(0);
+                    }
+                    self.memory.push((position, match_id));
+                }
+            }
+            GameEvent::Matched(first, second) => {
+                self.memory
+                    .retain(|&(known, _)| known != first && known != second);
+            }
+            _ => {}
+        }
+    }
+
+    // Called every frame while it is the bot's turn
+    pub fn think(&mut self, engine: &Engine) -> Option<Command> {
+        // Wait until both cards of the last try are resolved
+        if engine.selected().len() > 1 {
+            self.thinking_since = None;
+            return None;
+        }
+
+        let since = *self.thinking_since.get_or_insert(engine.played());
+        if engine.played().saturating_sub(since) < self.model.reaction {
+            return None;
+        }
+        self.thinking_since = None;
+
+        self.choose(engine).map(Command::FlipCard)
+    }
+
+    fn choose(&mut self, engine: &Engine) -> Option<Position> {
+        let mut hidden: Vec<Position> = engine
+            .cards()
+            .filter(|(_, card)| !card.face_up)
+            .map(|(position, _)| *position)
+            .collect();
+        // The map order is not stable, the bot's choice should be
+        hidden.sort();
+
+        let known: Vec<(Position, u32)> = self
+            .memory
+            .iter()
+            .filter(|(position, _)| hidden.contains(position))
+            .copied()
+            .collect();
+
+        let remembered = match engine.selected() {
+            // The partner of the card that is already up
+            [(first, match_id)] => known
+                .iter()
+                .find(|(position, id)| position != first && id == match_id)
+                .map(|(position, _)| *position),
+            // A whole pair that is known
+            _ => known
+                .iter()
+                .find(|(position, match_id)| {
+                    known
+                        .iter()
+                        .any(|(other, id)| other != position && id == match_id)
+                })
+                .map(|(position, _)| *position),
+        };
+        if remembered.is_some() {
+            return remembered;
+        }
+
+        // Otherwise try a card that is not known yet
+        let unknown: Vec<Position> = hidden
+            .iter()
+            .filter(|position| !known.iter().any(|(known, _)| known == *position))
+            .copied()
+            .collect();
+
+        if unknown.is_empty() {
+            hidden.choose(&mut self.rng).copied()
+        } else {
+            unknown.choose(&mut self.rng).copied()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Outcome, Rules};
+
+    const STEP: Duration = Duration::from_millis(100);
+
+    fn engine(seed: u64) -> Engine {
+        let rules = Rules {
+            duration: Duration::from_secs(3600),
+            bonuses: Vec::new(),
+            ..Rules::default()
+        };
+        Engine::new(6, 4, rules, seed)
+    }
+
+    // One bot plays a whole board alone. Gives every event in order.
+    fn play(level: BotLevel, seed: u64) -> Vec<GameEvent> {
+        let mut engine = engine(seed);
+        let mut bot = Bot::new(level.model(), seed);
+        let mut events = Vec::new();
+
+        while engine.outcome() == Outcome::Playing {
+            if let Some(command) = bot.think(&engine) {
+                engine.apply(command);
+            }
+            engine.tick(STEP);
+
+            for event in engine.drain_events() {
+                bot.observe(&event);
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    fn mismatches(events: &[GameEvent]) -> usize {
+        events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::NotMatched(..)))
+            .count()
+    }
+
+    #[test]
+    fn perfect_bot_never_misses_a_known_pair() {
+        for seed in 0..10 {
+            let events = play(BotLevel::Perfect, seed);
+            assert_eq!(events.last(), Some(&GameEvent::Won));
+
+            // Cards shown so far and still on the board
+            let mut seen: Vec<(Position, u32)> = Vec::new();
+            let mut up: Option<(Position, u32)> = None;
+            for event in events {
+                match event {
+                    GameEvent::CardFlipped(position, match_id) => {
+                        match up.take() {
+                            Some((first, first_id)) => {
+                                let partner_known = seen
+                                    .iter()
+                                    .any(|&(known, id)| known != first && id == first_id);
+                                assert!(
+                                    !partner_known || match_id == first_id,
+                                    "seed {}: {:?} was known, {:?} was turned",
+                                    seed,
+                                    first_id,
+                                    position
+                                );
+                            }
+                            None => up = Some((position, match_id)),
+                        }
+                        seen.retain(|&(known, _)| known != position);
+                        seen.push((position, match_id));
+                    }
+                    GameEvent::Matched(first, second) => {
+                        seen.retain(|&(known, _)| known != first && known != second);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_bot_remembers_nothing() {
+        let board = engine(1);
+        let mut cards: Vec<(Position, u32)> = board
+            .cards()
+            .map(|(position, card)| (*position, card.match_id))
+            .collect();
+        cards.sort();
+        let pair: Vec<Position> = cards
+            .iter()
+            .filter(|(_, id)| *id == 1)
+            .map(|(position, _)| *position)
+            .collect();
+
+        // Both bots saw the same pair, only the perfect one goes for it
+        let first_pick = |level: BotLevel, seed| {
+            let mut engine = board.clone();
+            let mut bot = Bot::new(level.model(), seed);
+            for &position in pair.iter() {
+                bot.observe(&GameEvent::CardFlipped(position, 1));
+            }
+
+            loop {
+                if let Some(Command::FlipCard(position)) = bot.think(&engine) {
+                    return position;
+                }
+                engine.tick(STEP);
+            }
+        };
+
+        for seed in 0..20 {
+            assert!(pair.contains(&first_pick(BotLevel::Perfect, seed)));
+        }
+        let random_hits = (0..20)
+            .filter(|seed| pair.contains(&first_pick(BotLevel::Random, *seed)))
+            .count();
+        assert!(random_hits < 10, "{} of 20", random_hits);
+    }
+
+    #[test]
+    fn better_bots_need_fewer_tries() {
+        let average = |level| -> f64 {
+            let total: usize = (0..20).map(|seed| mismatches(&play(level, seed))).sum();
+            total as f64 / 20.0
+        };
+
+        assert!(average(BotLevel::Perfect) < average(BotLevel::Medium));
+        assert!(average(BotLevel::Medium) < average(BotLevel::Random));
+    }
+}
@@ -1,156 +1,201 @@
+use std::time::Duration;
+
 use ggez::graphics;
+use ggez::graphics::{Color, DrawParam};
 use ggez::mint::Point2;
 use ggez::mint::Vector2;
-use ggez::{Context, GameResult};
-use std::path::Path;
 
-const CARD_WIDTH: u32 = 125;
+use crate::atlas::Atlas;
+use crate::tween;
+use crate::tween::CardAnimation;
 
-#[derive(Debug)]
-enum CardTurning {
-    TurnStart,
-    TurnMiddle,
-    TurnEnd,
+// Size a matched card shrinks to on its way to the score
+const DISMISSED_SCALE: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy)]
+struct Dismiss {
+    // Centre of the card at the end
+    target: Point2<f32>,
+    progress: f32,
 }
 
 #[derive(Debug)]
 pub struct Card {
-    card_back: graphics::Image,
-    card_front: graphics::Image,
     pub match_id: u32,
+    // Side that is up once the card has finished turning
     pub is_clicked: bool,
-    pub turning: bool,
-    turning_state: CardTurning,
-    pub is_matched: bool,
+    // From 0 to 1 while turning
+    turning: Option<f32>,
+    dismiss: Option<Dismiss>,
+    // Text printed over the face, from the deck
+    label: Option<graphics::Text>,
     dest: Point2<f32>,
+    size: Vector2<f32>,
 }
 
 impl Card {
-    pub fn new(
-        ctx: &mut Context,
-        card_back_path: &Path,
-        card_front_path: &Path,
-        dest: Point2<f32>,
-        match_id: u32,
-    ) -> GameResult<Card> {
-        let card_back = graphics::Image::from_path(ctx, card_back_path)?;
-        let card_front = graphics::Image::from_path(ctx, card_front_path)?;
-
-        Ok(Card {
-            card_back,
-            card_front,
+    pub fn new(dest: Point2<f32>, size: Vector2<f32>, match_id: u32) -> Card {
+        Card {
             match_id,
             is_clicked: false,
-            turning: false,
-            turning_state: CardTurning::TurnEnd,
-            is_matched: false,
+            turning: None,
+            dismiss: None,
+            label: None,
             dest,
-        })
+            size,
+        }
     }
 
-    pub fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        match self.turning_state {
-            CardTurning::TurnStart => {
-                // If is match do not use middle state
-                if self.is_matched {
-                    self.turning_state = CardTurning::TurnEnd;
-                } else {
-                    self.turning_state = CardTurning::TurnMiddle
-                }
-            }
-            CardTurning::TurnMiddle => {
-                self.turning_state = CardTurning::TurnEnd;
-                self.is_clicked = !self.is_clicked;
-                self.turning = false;
-            }
-            CardTurning::TurnEnd => {
-                if self.turning || self.is_matched {
-                    self.turning_state = CardTurning::TurnStart;
-                }
-            }
+    // A matched card first finishes turning up, then goes
+    pub fn update(&mut self, dt: Duration, animation: &CardAnimation) {
+        if let Some(progress) = self.turning {
+            let progress = tween::advance(progress, dt, animation.flip);
+            self.turning = (progress < 1.0).then_some(progress);
+        } else if let Some(dismiss) = self.dismiss.as_mut() {
+            dismiss.progress = tween::advance(dismiss.progress, dt, animation.dismiss);
         }
-
-        Ok(())
     }
 
-    pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
-        let mut dest: Point2<f32> = self.dest;
-        if self.turning {
-            match self.turning_state {
-                CardTurning::TurnStart => {
-                    dest.x += (CARD_WIDTH / 2) as f32;
-                    if self.is_clicked {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.20, y: 0.50 })
-                            .offset(Vector2 { x: 0.50, y: 0.0 });
-
-                        canvas.draw(&self.card_front, draw_params);
-                    } else {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.20, y: 0.50 })
-                            .offset(Vector2 { x: 0.50, y: 0.0 });
-
-                        canvas.draw(&self.card_back, draw_params);
-                    }
-                }
-                CardTurning::TurnMiddle => {
-                    dest.x += (CARD_WIDTH / 2) as f32;
-                    if self.is_clicked {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.20, y: 0.50 })
-                            .offset(Vector2 { x: 0.50, y: 0.0 });
-
-                        canvas.draw(&self.card_back, draw_params);
-                    } else {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.20, y: 0.50 })
-                            .offset(Vector2 { x: 0.50, y: 0.0 });
-                        canvas.draw(&self.card_front, draw_params);
-                    }
-                }
-                CardTurning::TurnEnd => {
-                    if self.is_matched {
-                        return Ok(());
-                    }
-                    if self.is_clicked {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.50, y: 0.50 });
-
-                        canvas.draw(&self.card_front, draw_params);
-                    } else {
-                        let draw_params = graphics::DrawParam::default()
-                            .dest(dest)
-                            .scale(Vector2 { x: 0.50, y: 0.50 });
-
-                        canvas.draw(&self.card_back, draw_params);
-                    }
-                }
+    // Which side shows and how wide it is, relative to the full width
+    fn look(&self, animation: &CardAnimation) -> (bool, f32) {
+        match self.turning {
+            Some(progress) => {
+                let t = animation.flip_easing.apply(progress);
+
+                // The old side turns away for the first half
+                (self.is_clicked == (t >= 0.5), (1. - 2. * t).abs())
             }
+            None => (self.is_clicked, 1.0),
+        }
+    }
+
+    // The card as one instance of the board's batch
+    pub fn instance(&self, atlas: &Atlas, animation: &CardAnimation) -> Option<DrawParam> {
+        if self.is_gone() {
+            return None;
+        }
+
+        let (front, width) = self.look(animation);
+        let src = if front {
+            atlas.face(self.match_id)
         } else {
-            if self.is_clicked {
-                let draw_params = graphics::DrawParam::default()
-                    .dest(self.dest)
-                    .scale(Vector2 { x: 0.50, y: 0.50 });
-
-                canvas.draw(&self.card_front, draw_params);
-            } else {
-                let draw_params = graphics::DrawParam::default()
-                    .dest(self.dest)
-                    .scale(Vector2 { x: 0.50, y: 0.50 });
-
-                canvas.draw(&self.card_back, draw_params);
+            atlas.back()
+        };
+        let scale = atlas.scale(self.size);
+
+        // Turning squeezes the card towards its middle, leaving shrinks it
+        // around its centre on the way to the score
+        let (centre, size, alpha) = match self.dismiss.filter(|_| self.turning.is_none()) {
+            Some(dismiss) => {
+                let t = animation.dismiss_easing.apply(dismiss.progress);
+                let centre = self.centre();
+
+                (
+                    Point2 {
+                        x: tween::lerp(centre.x, dismiss.target.x, t),
+                        y: tween::lerp(centre.y, dismiss.target.y, t),
+                    },
+                    tween::lerp(1.0, DISMISSED_SCALE, t),
+                    1. - t,
+                )
             }
+            None => (self.centre(), 1.0, 1.0),
+        };
+
+        Some(
+            DrawParam::default()
+                .src(src)
+                .dest(Point2 {
+                    x: centre.x - self.size.x * size * width / 2.,
+                    y: centre.y - self.size.y * size / 2.,
+                })
+                .scale(Vector2 {
+                    x: scale.x * size * width,
+                    y: scale.y * size,
+                })
+                .color(Color::new(1.0, 1.0, 1.0, alpha)),
+        )
+    }
+
+    pub fn set_label(&mut self, label: Option<&str>) {
+        self.label = label.map(|label| {
+            let mut text = graphics::Text::new(label);
+            text.set_scale(self.size.x / 6.);
+            text
+        });
+    }
+
+    // Along the bottom edge of the face, while it is fully turned up
+    pub fn draw_label(&self, canvas: &mut graphics::Canvas) {
+        let label = match &self.label {
+            Some(label) if self.is_clicked && self.turning.is_none() && self.dismiss.is_none() => {
+                label
+            }
+            _ => return,
+        };
+
+        canvas.draw(
+            label,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: self.dest.x + self.size.x / 2.,
+                    y: self.dest.y + self.size.y * 0.95,
+                })
+                .offset(Point2 { x: 0.5, y: 1.0 })
+                .color(Color::BLACK),
+        );
+    }
+
+    pub fn set_place(&mut self, dest: Point2<f32>, size: Vector2<f32>) {
+        self.dest = dest;
+        self.size = size;
+        if let Some(label) = self.label.as_mut() {
+            label.set_scale(size.x / 6.);
         }
+    }
 
-        Ok(())
+    // Network games only learn what a card is once it is turned over
+    pub fn set_front(&mut self, match_id: u32) {
+        self.match_id = match_id;
     }
 
+    // Turns the card over. A card that is still turning goes back the way
+    // it came.
     pub fn click(&mut self) {
-        self.turning = true
+        self.is_clicked = !self.is_clicked;
+        self.turning = Some(self.turning.map_or(0.0, |progress| 1. - progress));
+    }
+
+    // Sends a matched card off towards `target`
+    pub fn dismiss(&mut self, target: Point2<f32>) {
+        self.dismiss = Some(Dismiss {
+            target,
+            progress: 0.0,
+        });
+    }
+
+    pub fn is_dismissed(&self) -> bool {
+        self.dismiss.is_some()
+    }
+
+    // Done with the dismiss animation, the card can be dropped
+    pub fn is_gone(&self) -> bool {
+        self.dismiss.is_some_and(|dismiss| dismiss.progress >= 1.0)
+    }
+
+    fn centre(&self) -> Point2<f32> {
+        Point2 {
+            x: self.dest.x + self.size.x / 2.,
+            y: self.dest.y + self.size.y / 2.,
+        }
+    }
+
+    // A card on its way out can no longer be picked
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        !self.is_dismissed()
+            && self.dest.x <= x
+            && self.dest.x + self.size.x >= x
+            && self.dest.y <= y
+            && self.dest.y + self.size.y >= y
     }
 }
@@ -0,0 +1,239 @@
+use std::time::{Duration, Instant};
+
+// Source of game time. Everything that measures time in the game goes
+// through a clock so it can be faked, paused or slowed down.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+// Wall clock time since the clock was created
+#[derive(Debug, Clone)]
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> RealClock {
+        RealClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> RealClock {
+        RealClock::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// Clock that only moves when told to, for tests and replays
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Duration,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&mut self, dt: Duration) {
+        self.now += dt;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+}
+
+// Runs another clock faster or slower. A scale of 0 stops the time.
+#[derive(Debug, Clone)]
+pub struct ScaledClock<C: Clock> {
+    inner: C,
+    scale: f64,
+    paused: bool,
+    anchor_inner: Duration,
+    anchor_scaled: Duration,
+}
+
+impl<C: Clock> ScaledClock<C> {
+    pub fn new(inner: C, scale: f64) -> ScaledClock<C> {
+        let anchor_inner = inner.now();
+
+        ScaledClock {
+            inner,
+            scale: scale.max(0.0),
+            paused: false,
+            anchor_inner,
+            anchor_scaled: Duration::ZERO,
+        }
+    }
+
+    // While paused the clock keeps returning the time it was paused at
+    pub fn pause(&mut self) {
+        self.anchor_scaled = self.now();
+        self.anchor_inner = self.inner.now();
+        self.paused = true;
+    }
+
+    // A running clock keeps its anchors, moving them would take the time
+    // since the last one back
+    pub fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.anchor_inner = self.inner.now();
+        self.paused = false;
+    }
+
+    // To move a manual clock underneath
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+impl<C: Clock> Clock for ScaledClock<C> {
+    fn now(&self) -> Duration {
+        if self.paused {
+            return self.anchor_scaled;
+        }
+
+        let passed = self.inner.now().saturating_sub(self.anchor_inner);
+        self.anchor_scaled + passed.mul_f64(self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_timer::GameTimer;
+    use memory_game::engine::{Engine, Outcome, Rules};
+
+    const FRAME: Duration = Duration::from_millis(250);
+
+    // What the game does every frame: the engine and the timer get the time
+    // the game clock moved since the last frame
+    struct Game {
+        clock: ScaledClock<ManualClock>,
+        last_tick: Duration,
+        engine: Engine,
+        timer: GameTimer,
+    }
+
+    impl Game {
+        fn new(speed: f64) -> Game {
+            let rules = Rules {
+                duration: Duration::from_secs(30),
+                ..Rules::default()
+            };
+            let engine = Engine::new(4, 3, rules, 1);
+            let timer = GameTimer::new(engine.remaining());
+
+            Game {
+                clock: ScaledClock::new(ManualClock::new(), speed),
+                last_tick: Duration::ZERO,
+                engine,
+                timer,
+            }
+        }
+
+        fn frames(&mut self, count: u32) {
+            for _ in 0..count {
+                self.clock.inner_mut().advance(FRAME);
+
+                let now = self.clock.now();
+                self.engine.tick(now.saturating_sub(self.last_tick));
+                self.last_tick = now;
+                self.timer.update(self.engine.remaining()).unwrap();
+            }
+        }
+
+        fn shown(&self) -> String {
+            self.timer.text.contents()
+        }
+    }
+
+    #[test]
+    fn scaled_clock_runs_at_its_speed() {
+        let mut clock = ScaledClock::new(ManualClock::new(), 2.0);
+
+        clock.inner_mut().advance(Duration::from_secs(3));
+        assert_eq!(clock.now(), Duration::from_secs(6));
+
+        let mut stopped = ScaledClock::new(ManualClock::new(), 0.0);
+        stopped.inner_mut().advance(Duration::from_secs(3));
+        assert_eq!(stopped.now(), Duration::ZERO);
+    }
+
+    #[test]
+    fn paused_clock_picks_up_where_it_stopped() {
+        let mut clock = ScaledClock::new(ManualClock::new(), 1.0);
+
+        clock.inner_mut().advance(Duration::from_secs(2));
+        clock.pause();
+        clock.inner_mut().advance(Duration::from_secs(10));
+        assert_eq!(clock.now(), Duration::from_secs(2));
+
+        clock.resume();
+        clock.inner_mut().advance(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn resuming_a_running_clock_keeps_its_time() {
+        let mut clock = ScaledClock::new(ManualClock::new(), 2.0);
+
+        clock.inner_mut().advance(Duration::from_secs(3));
+        clock.resume();
+        assert_eq!(clock.now(), Duration::from_secs(6));
+
+        clock.inner_mut().advance(Duration::from_secs(1));
+        clock.resume();
+        clock.resume();
+        assert_eq!(clock.now(), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn timer_counts_down_with_the_game_clock() {
+        let mut game = Game::new(1.0);
+
+        game.frames(4 * 5);
+        assert_eq!(game.engine.remaining(), Duration::from_secs(25));
+        assert_eq!(game.shown(), "00:25");
+
+        game.frames(4 * 25);
+        assert_eq!(game.engine.outcome(), Outcome::Lost);
+    }
+
+    #[test]
+    fn speed_and_pause_reach_the_timer() {
+        let mut game = Game::new(2.0);
+
+        game.frames(4 * 5);
+        assert_eq!(game.shown(), "00:20");
+
+        // A paused game loses no time however many frames go by
+        game.clock.pause();
+        game.frames(4 * 60);
+        assert_eq!(game.shown(), "00:20");
+        assert_eq!(game.engine.outcome(), Outcome::Playing);
+
+        game.clock.resume();
+        game.frames(4);
+        assert_eq!(game.shown(), "00:18");
+    }
+}
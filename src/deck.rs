@@ -0,0 +1,145 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ggez::{Context, GameError, GameResult};
+use serde::Deserialize;
+
+use crate::resources;
+
+pub const MANIFEST: &str = "deck.toml";
+pub const DEFAULT_DECK: &str = "cards";
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: String,
+    back: String,
+    faces: Vec<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+// Why a deck can not be played, the game shows it in the menu language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckError {
+    // Directory without a deck in it
+    Missing(String),
+    TooSmall {
+        name: String,
+        faces: usize,
+        pairs: usize,
+    },
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckError::Missing(dir) => write!(
+                f,
+                "There is no deck \"{}\", it needs a {} in resources/{}",
+                dir, MANIFEST, dir
+            ),
+            DeckError::TooSmall { name, faces, pairs } => write!(
+                f,
+                "The deck \"{}\" has {} faces, but the board needs {} pairs",
+                name, faces, pairs
+            ),
+        }
+    }
+}
+
+// A set of card images from one directory under resources. Match id `n`
+// shows the `n`th face.
+#[derive(Debug, Clone)]
+pub struct Deck {
+    // Directory name, this is what levels, replays and the server refer to
+    pub dir: String,
+    pub name: String,
+    back: PathBuf,
+    faces: Vec<PathBuf>,
+    labels: Vec<String>,
+}
+
+impl Deck {
+    pub fn parse(dir: &str, source: &str) -> Result<Deck, String> {
+        let manifest: Manifest = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        if manifest.faces.is_empty() {
+            return Err(format!("deck \"{}\" has no faces", manifest.name));
+        }
+        if manifest.labels.len() > manifest.faces.len() {
+            return Err(format!(
+                "deck \"{}\" has {} labels for {} faces",
+                manifest.name,
+                manifest.labels.len(),
+                manifest.faces.len()
+            ));
+        }
+
+        let image = |file: &str| PathBuf::from(format!("/{}/{}", dir, file));
+
+        Ok(Deck {
+            dir: dir.to_owned(),
+            name: manifest.name,
+            back: image(&manifest.back),
+            faces: manifest.faces.iter().map(|file| image(file)).collect(),
+            labels: manifest.labels,
+        })
+    }
+
+    pub fn load(ctx: &mut Context, dir: &str) -> GameResult<Deck> {
+        let path = format!("/{}/{}", dir, MANIFEST);
+
+        let mut source = String::new();
+        ctx.fs.open(&path)?.read_to_string(&mut source)?;
+
+        Deck::parse(dir, &source)
+            .map_err(|e| GameError::ResourceLoadError(format!("{}: {}", path, e)))
+    }
+
+    // Most pairs a board can have with this deck
+    pub fn pairs(&self) -> usize {
+        self.faces.len()
+    }
+
+    pub fn back(&self) -> &Path {
+        &self.back
+    }
+
+    pub fn faces(&self) -> impl Iterator<Item = &Path> {
+        self.faces.iter().map(PathBuf::as_path)
+    }
+
+    pub fn label(&self, match_id: u32) -> Option<&str> {
+        let index = (match_id as usize).checked_sub(1)?;
+
+        self.labels
+            .get(index)
+            .map(String::as_str)
+            .filter(|label| !label.is_empty())
+    }
+
+    pub fn check(&self, pairs: usize) -> Result<(), DeckError> {
+        if pairs > self.pairs() {
+            return Err(DeckError::TooSmall {
+                name: self.name.clone(),
+                faces: self.pairs(),
+                pairs,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub fn find<'a>(decks: &'a [Deck], dir: &str) -> Result<&'a Deck, DeckError> {
+    decks
+        .iter()
+        .find(|deck| deck.dir == dir)
+        .ok_or_else(|| DeckError::Missing(dir.to_owned()))
+}
+
+// Every directory under resources with a manifest is a deck
+pub fn discover(ctx: &mut Context) -> Vec<Deck> {
+    resources::discover(ctx, MANIFEST, "deck", Deck::load)
+}
@@ -0,0 +1,820 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::score::Score;
+
+// Time the player gets to look at both cards before the pair is resolved
+const FLIP_BACK_DELAY: Duration = Duration::from_secs(1);
+const BONUS_TIME_REWARD: Duration = Duration::from_secs(15);
+const STRIKE_FOR_BONUS_TIME: usize = 2;
+
+// Grid cell of a card as (column, row)
+pub type Position = (u32, u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BonusState {
+    Used,
+    NotUsed,
+    Using,
+    NotActive,
+    // Not allowed on the current level
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BonusKind {
+    BonusTime,
+    FreezeTime,
+    FreeMatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Command {
+    FlipCard(Position),
+    UseBonus(BonusKind),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    // The card and the match id that is now shown to everyone
+    CardFlipped(Position, u32),
+    Matched(Position, Position),
+    NotMatched(Position, Position),
+    BonusUnlocked(BonusKind),
+    BonusActivated(BonusKind),
+    BonusExpired(BonusKind),
+    // Index of the player whose turn it is now
+    TurnChanged(usize),
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Playing,
+    Won,
+    Lost,
+}
+
+// Per level tuning of the rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rules {
+    pub duration: Duration,
+    pub match_reward: Duration,
+    pub mismatch_penalty: Duration,
+    pub bonuses: Vec<BonusKind>,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            duration: Duration::ZERO,
+            match_reward: Duration::from_secs(5),
+            mismatch_penalty: Duration::from_secs(2),
+            bonuses: vec![
+                BonusKind::BonusTime,
+                BonusKind::FreezeTime,
+                BonusKind::FreeMatch,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardCard {
+    pub match_id: u32,
+    pub face_up: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BonusSlot {
+    pub state: BonusState,
+    pub started: Option<Duration>,
+    pub duration: Duration,
+}
+
+impl BonusSlot {
+    pub fn new(state: BonusState, duration: Duration) -> BonusSlot {
+        BonusSlot {
+            state,
+            started: None,
+            duration,
+        }
+    }
+
+    fn activate(&mut self, now: Duration) -> bool {
+        if self.state != BonusState::NotUsed {
+            return false;
+        }
+
+        self.state = BonusState::Using;
+        self.started = Some(now);
+        true
+    }
+
+    // Returns true when the bonus has just run out
+    fn update(&mut self, now: Duration) -> bool {
+        if self.state != BonusState::Using {
+            return false;
+        }
+
+        match self.started {
+            Some(start) if start + self.duration < now => {
+                self.state = BonusState::Used;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+// The whole state of a game, which is also what gets saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Engine {
+    seed: u64,
+    columns: u32,
+    rows: u32,
+    #[serde(
+        serialize_with = "serialize_cards",
+        deserialize_with = "deserialize_cards"
+    )]
+    cards: HashMap<Position, BoardCard>,
+    selected: Vec<(Position, u32)>,
+    now: Duration,
+    last_flip: Option<Duration>,
+    rules: Rules,
+    duration: Duration,
+    elapsed: Duration,
+    match_strike: usize,
+    // Cards that were already shown to the player and flipped back
+    seen: HashSet<Position>,
+    score: Score,
+    // One score per player in a hot-seat game, empty for a single player
+    #[serde(default)]
+    player_scores: Vec<Score>,
+    #[serde(default)]
+    turn: usize,
+    bonus_time: BonusSlot,
+    freeze_time: BonusSlot,
+    free_match: BonusSlot,
+    outcome: Outcome,
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+}
+
+// Cards are stored as a list because the positions are not valid map keys in
+// most formats
+fn serialize_cards<S: Serializer>(
+    cards: &HashMap<Position, BoardCard>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut list: Vec<(&Position, &BoardCard)> = cards.iter().collect();
+    list.sort_by_key(|(position, _)| **position);
+    list.serialize(serializer)
+}
+
+fn deserialize_cards<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<Position, BoardCard>, D::Error> {
+    let list: Vec<(Position, BoardCard)> = Vec::deserialize(deserializer)?;
+    Ok(list.into_iter().collect())
+}
+
+impl Engine {
+    // The same seed always gives the same board for the same grid size
+    pub fn new(columns: u32, rows: u32, rules: Rules, seed: u64) -> Engine {
+        // Callers check the size, this only keeps a silly one from panicking
+        let pairs = columns.saturating_mul(rows) / 2;
+        let mut card_ids: Vec<u32> = (1..=pairs).chain(1..=pairs).collect();
+
+        let mut rnd = ChaCha8Rng::seed_from_u64(seed);
+        card_ids.shuffle(&mut rnd);
+
+        let mut engine = Engine::with_layout(columns, rows, card_ids, rules);
+        engine.seed = seed;
+        engine
+    }
+
+    // Builds a board from an already ordered list of match ids, filled row by
+    // row from the end of the list
+    pub fn with_layout(columns: u32, rows: u32, mut card_ids: Vec<u32>, rules: Rules) -> Engine {
+        let mut cards = HashMap::new();
+
+        for position in Engine::cells(columns, rows) {
+            match card_ids.pop() {
+                Some(match_id) => {
+                    cards.insert(
+                        position,
+                        BoardCard {
+                            match_id,
+                            face_up: false,
+                        },
+                    );
+                }
+                None => break,
+            }
+        }
+
+        let slot = |kind: BonusKind, state: BonusState, duration: Duration| {
+            if rules.bonuses.contains(&kind) {
+                BonusSlot::new(state, duration)
+            } else {
+                BonusSlot::new(BonusState::Disabled, duration)
+            }
+        };
+
+        let bonus_time = slot(
+            BonusKind::BonusTime,
+            BonusState::NotActive,
+            Duration::new(2, 0),
+        );
+        let freeze_time = slot(
+            BonusKind::FreezeTime,
+            BonusState::NotUsed,
+            Duration::new(15, 0),
+        );
+        let free_match = slot(
+            BonusKind::FreeMatch,
+            BonusState::NotUsed,
+            Duration::new(1, 0),
+        );
+
+        Engine {
+            seed: 0,
+            columns,
+            rows,
+            cards,
+            selected: Vec::new(),
+            now: Duration::ZERO,
+            last_flip: None,
+            duration: rules.duration,
+            rules,
+            elapsed: Duration::ZERO,
+            match_strike: 0,
+            seen: HashSet::new(),
+            score: Score::default(),
+            player_scores: Vec::new(),
+            turn: 0,
+            bonus_time,
+            freeze_time,
+            free_match,
+            outcome: Outcome::Playing,
+            events: Vec::new(),
+        }
+    }
+
+    // Players take turns, a match keeps the turn and a mismatch passes it on
+    pub fn with_players(mut self, players: usize) -> Engine {
+        self.player_scores = if players > 1 {
+            vec![Score::default(); players]
+        } else {
+            Vec::new()
+        };
+        self.turn = 0;
+        self
+    }
+
+    // Cells that hold a card, row by row. With an odd number of cells the
+    // middle one stays empty.
+    pub fn cells(columns: u32, rows: u32) -> Vec<Position> {
+        let mut cells: Vec<Position> = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .collect();
+
+        if cells.len() % 2 == 1 {
+            cells.remove(cells.len() / 2);
+        }
+
+        cells
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = (&Position, &BoardCard)> {
+        self.cards.iter()
+    }
+
+    pub fn card(&self, position: Position) -> Option<&BoardCard> {
+        self.cards.get(&position)
+    }
+
+    pub fn selected(&self) -> &[(Position, u32)] {
+        &self.selected
+    }
+
+    // Game time since the start, including frozen time
+    pub fn played(&self) -> Duration {
+        self.now
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed)
+    }
+
+    pub fn match_strike(&self) -> usize {
+        self.match_strike
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    pub fn players(&self) -> usize {
+        self.player_scores.len().max(1)
+    }
+
+    pub fn player_scores(&self) -> &[Score] {
+        &self.player_scores
+    }
+
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    // In a race everybody plays at once, whoever turns the first card of a
+    // pair gets the pair
+    pub fn claim_turn(&mut self, player: usize) -> bool {
+        if player >= self.players() || !self.selected.is_empty() {
+            return self.turn == player;
+        }
+
+        if self.turn != player {
+            self.turn = player;
+            self.events.push(GameEvent::TurnChanged(player));
+        }
+        true
+    }
+
+    // Gives the turn to the next player, for one who left the game. A card
+    // that is already up stays for the next player to pair.
+    pub fn pass_turn(&mut self) {
+        if self.players() > 1 {
+            self.turn = (self.turn + 1) % self.players();
+            self.events.push(GameEvent::TurnChanged(self.turn));
+        }
+    }
+
+    // Points go to the whole game and to the player whose turn it is
+    fn add_score(&mut self, add: impl Fn(&mut Score)) {
+        add(&mut self.score);
+        if let Some(score) = self.player_scores.get_mut(self.turn) {
+            add(score);
+        }
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    pub fn bonus(&self, kind: BonusKind) -> &BonusSlot {
+        match kind {
+            BonusKind::BonusTime => &self.bonus_time,
+            BonusKind::FreezeTime => &self.freeze_time,
+            BonusKind::FreeMatch => &self.free_match,
+        }
+    }
+
+    fn bonus_mut(&mut self, kind: BonusKind) -> &mut BonusSlot {
+        match kind {
+            BonusKind::BonusTime => &mut self.bonus_time,
+            BonusKind::FreezeTime => &mut self.freeze_time,
+            BonusKind::FreeMatch => &mut self.free_match,
+        }
+    }
+
+    // Game time after which a tick does something by itself, turning a pair
+    // back or ending a bonus. The countdown running out is not counted.
+    pub fn next_due(&self) -> Option<Duration> {
+        let resolve = self
+            .last_flip
+            .filter(|_| self.selected.len() == 2)
+            .map(|time| time + FLIP_BACK_DELAY);
+        let bonuses = [&self.bonus_time, &self.freeze_time, &self.free_match]
+            .into_iter()
+            .filter(|slot| slot.state == BonusState::Using)
+            .filter_map(|slot| slot.started.map(|start| start + slot.duration));
+
+        resolve.into_iter().chain(bonuses).min()
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.freeze_time.state == BonusState::Using
+    }
+
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn apply(&mut self, command: Command) {
+        if self.outcome != Outcome::Playing {
+            return;
+        }
+
+        match command {
+            Command::FlipCard(position) => {
+                if self.selected.len() < 2 {
+                    self.flip(position);
+                }
+            }
+            Command::UseBonus(kind) => self.use_bonus(kind),
+        }
+    }
+
+    pub fn tick(&mut self, dt: Duration) {
+        if self.outcome != Outcome::Playing {
+            return;
+        }
+
+        self.now += dt;
+
+        for kind in [
+            BonusKind::BonusTime,
+            BonusKind::FreezeTime,
+            BonusKind::FreeMatch,
+        ] {
+            let now = self.now;
+            if self.bonus_mut(kind).update(now) {
+                self.events.push(GameEvent::BonusExpired(kind));
+            }
+        }
+
+        // Freeze time bonus stops the countdown
+        if !self.is_frozen() {
+            self.elapsed += dt;
+        }
+
+        // Give some time for the card to flip before checking for match
+        if let Some(time) = self.last_flip {
+            if self.selected.len() == 2 && time + FLIP_BACK_DELAY < self.now {
+                self.resolve_selected();
+            }
+        }
+
+        // Check for match strike
+        if self.match_strike >= STRIKE_FOR_BONUS_TIME
+            && self.bonus_time.state == BonusState::NotActive
+        {
+            self.bonus_time.state = BonusState::NotUsed;
+            self.events
+                .push(GameEvent::BonusUnlocked(BonusKind::BonusTime));
+        }
+
+        if self.cards.is_empty() {
+            self.outcome = Outcome::Won;
+            self.score.add_time_left(self.remaining());
+            self.events.push(GameEvent::Won);
+        } else if self.remaining().is_zero() {
+            self.outcome = Outcome::Lost;
+            self.events.push(GameEvent::Lost);
+        }
+    }
+
+    fn flip(&mut self, position: Position) -> bool {
+        let card = match self.cards.get_mut(&position) {
+            Some(card) if !card.face_up => card,
+            _ => return false,
+        };
+
+        card.face_up = true;
+        self.selected.push((position, card.match_id));
+        self.last_flip = Some(self.now);
+        self.events
+            .push(GameEvent::CardFlipped(position, card.match_id));
+
+        true
+    }
+
+    fn use_bonus(&mut self, kind: BonusKind) {
+        // The hint needs a free slot in the current selection
+        if kind == BonusKind::FreeMatch && self.selected.len() > 1 {
+            return;
+        }
+
+        let now = self.now;
+        if !self.bonus_mut(kind).activate(now) {
+            return;
+        }
+
+        self.add_score(Score::add_bonus_used);
+        self.events.push(GameEvent::BonusActivated(kind));
+
+        match kind {
+            BonusKind::BonusTime => self.duration += BONUS_TIME_REWARD,
+            BonusKind::FreezeTime => {}
+            BonusKind::FreeMatch => self.reveal_pair(),
+        }
+    }
+
+    // Flips a whole pair, or the partner of the single selected card
+    fn reveal_pair(&mut self) {
+        if self.selected.is_empty() {
+            let first = self
+                .cards
+                .iter()
+                .filter(|(_, card)| !card.face_up)
+                .map(|(position, _)| *position)
+                .min_by_key(|&(column, row)| (row, column));
+
+            if let Some(position) = first {
+                self.flip(position);
+            }
+        }
+
+        if self.selected.len() == 1 {
+            let (selected_position, match_id) = self.selected[0];
+            let partner = self
+                .cards
+                .iter()
+                .find(|(position, card)| {
+                    **position != selected_position && card.match_id == match_id
+                })
+                .map(|(position, _)| *position);
+
+            if let Some(position) = partner {
+                self.flip(position);
+            }
+        }
+    }
+
+    fn resolve_selected(&mut self) {
+        let (first, first_id) = self.selected[0];
+        let (second, second_id) = self.selected[1];
+
+        if first_id == second_id {
+            self.cards.remove(&first);
+            self.cards.remove(&second);
+
+            if !self.is_frozen() {
+                self.duration += self.rules.match_reward;
+            }
+
+            self.match_strike += 1;
+            let strike = self.match_strike;
+            self.add_score(|score| score.add_match(strike));
+            self.events.push(GameEvent::Matched(first, second));
+        } else {
+            for position in [first, second] {
+                if let Some(card) = self.cards.get_mut(&position) {
+                    card.face_up = false;
+                }
+            }
+
+            if !self.is_frozen() {
+                self.duration = self.duration.saturating_sub(self.rules.mismatch_penalty);
+            }
+
+            // The player could have known better
+            if self.seen.contains(&first) || self.seen.contains(&second) {
+                self.add_score(Score::add_mistake);
+            }
+            self.seen.insert(first);
+            self.seen.insert(second);
+
+            self.match_strike = 0;
+            self.events.push(GameEvent::NotMatched(first, second));
+
+            self.pass_turn();
+        }
+
+        self.selected.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Long enough for a pair to be resolved
+    const STEP: Duration = Duration::from_millis(1100);
+
+    fn rules() -> Rules {
+        Rules {
+            duration: Duration::from_secs(60),
+            ..Rules::default()
+        }
+    }
+
+    // Pairs next to each other: (0, 0) and (1, 0) are 1, (0, 1) and (1, 1)
+    // are 2 and so on down the rows
+    fn paired(rows: u32) -> Engine {
+        let mut ids: Vec<u32> = (1..=rows).flat_map(|id| [id, id]).collect();
+        ids.reverse();
+        Engine::with_layout(2, rows, ids, rules())
+    }
+
+    fn layout(engine: &Engine) -> Vec<(Position, u32)> {
+        let mut cards: Vec<_> = engine
+            .cards()
+            .map(|(position, card)| (*position, card.match_id))
+            .collect();
+        cards.sort();
+        cards
+    }
+
+    fn flip_pair(engine: &mut Engine, first: Position, second: Position) -> Vec<GameEvent> {
+        engine.apply(Command::FlipCard(first));
+        engine.apply(Command::FlipCard(second));
+        engine.tick(STEP);
+        engine.drain_events()
+    }
+
+    #[test]
+    fn a_match_takes_the_pair_and_gives_time() {
+        let mut engine = paired(2);
+
+        let events = flip_pair(&mut engine, (0, 0), (1, 0));
+
+        assert_eq!(
+            events,
+            [
+                GameEvent::CardFlipped((0, 0), 1),
+                GameEvent::CardFlipped((1, 0), 1),
+                GameEvent::Matched((0, 0), (1, 0)),
+            ]
+        );
+        assert!(engine.card((0, 0)).is_none());
+        assert!(engine.score().matches > 0);
+        assert_eq!(engine.remaining(), Duration::from_secs(65) - STEP);
+    }
+
+    #[test]
+    fn a_mismatch_costs_time_and_turns_the_cards_back() {
+        let mut engine = paired(2);
+
+        let events = flip_pair(&mut engine, (0, 0), (0, 1));
+
+        assert_eq!(events.last(), Some(&GameEvent::NotMatched((0, 0), (0, 1))));
+        assert!(!engine.card((0, 0)).unwrap().face_up);
+        assert!(!engine.card((0, 1)).unwrap().face_up);
+        assert_eq!(engine.remaining(), Duration::from_secs(58) - STEP);
+        assert_eq!(engine.score().mistakes, 0);
+
+        // Now both were seen, so the same mistake counts
+        flip_pair(&mut engine, (0, 0), (0, 1));
+        assert!(engine.score().mistakes > 0);
+    }
+
+    #[test]
+    fn a_strike_unlocks_bonus_time() {
+        let mut engine = paired(3);
+
+        flip_pair(&mut engine, (0, 0), (1, 0));
+        assert_eq!(
+            engine.bonus(BonusKind::BonusTime).state,
+            BonusState::NotActive
+        );
+
+        let events = flip_pair(&mut engine, (0, 1), (1, 1));
+        assert!(events.contains(&GameEvent::BonusUnlocked(BonusKind::BonusTime)));
+        assert_eq!(
+            engine.bonus(BonusKind::BonusTime).state,
+            BonusState::NotUsed
+        );
+
+        let remaining = engine.remaining();
+        engine.apply(Command::UseBonus(BonusKind::BonusTime));
+        assert_eq!(engine.remaining(), remaining + BONUS_TIME_REWARD);
+        assert_eq!(
+            engine.drain_events(),
+            [GameEvent::BonusActivated(BonusKind::BonusTime)]
+        );
+    }
+
+    #[test]
+    fn bonus_time_is_locked_until_a_strike() {
+        let mut engine = paired(2);
+        let remaining = engine.remaining();
+
+        engine.apply(Command::UseBonus(BonusKind::BonusTime));
+
+        assert_eq!(engine.remaining(), remaining);
+        assert!(engine.drain_events().is_empty());
+    }
+
+    #[test]
+    fn freeze_stops_the_clock_until_it_runs_out() {
+        let mut engine = paired(2);
+
+        engine.apply(Command::UseBonus(BonusKind::FreezeTime));
+        engine.tick(Duration::from_secs(10));
+        assert_eq!(engine.remaining(), Duration::from_secs(60));
+
+        // No penalty while frozen either
+        flip_pair(&mut engine, (0, 0), (0, 1));
+        assert_eq!(engine.remaining(), Duration::from_secs(60));
+
+        engine.tick(Duration::from_secs(5));
+        assert!(engine
+            .drain_events()
+            .contains(&GameEvent::BonusExpired(BonusKind::FreezeTime)));
+        assert_eq!(engine.bonus(BonusKind::FreezeTime).state, BonusState::Used);
+
+        let remaining = engine.remaining();
+        engine.tick(Duration::from_secs(1));
+        assert_eq!(engine.remaining(), remaining - Duration::from_secs(1));
+    }
+
+    #[test]
+    fn free_match_turns_up_a_pair() {
+        let mut engine = paired(2);
+
+        engine.apply(Command::UseBonus(BonusKind::FreeMatch));
+        engine.tick(STEP);
+
+        assert_eq!(
+            engine.drain_events(),
+            [
+                GameEvent::BonusActivated(BonusKind::FreeMatch),
+                GameEvent::CardFlipped((0, 0), 1),
+                GameEvent::CardFlipped((1, 0), 1),
+                GameEvent::BonusExpired(BonusKind::FreeMatch),
+                GameEvent::Matched((0, 0), (1, 0)),
+            ]
+        );
+        assert_eq!(engine.bonus(BonusKind::FreeMatch).state, BonusState::Used);
+        assert!(engine.score().bonuses > 0);
+    }
+
+    #[test]
+    fn free_match_finds_the_partner_of_a_turned_card() {
+        let mut engine = paired(2);
+
+        engine.apply(Command::FlipCard((0, 1)));
+        engine.apply(Command::UseBonus(BonusKind::FreeMatch));
+
+        assert_eq!(engine.selected(), [((0, 1), 2), ((1, 1), 2)]);
+    }
+
+    #[test]
+    fn clearing_the_board_wins() {
+        let mut engine = paired(2);
+
+        flip_pair(&mut engine, (0, 0), (1, 0));
+        let events = flip_pair(&mut engine, (0, 1), (1, 1));
+
+        assert_eq!(events.last(), Some(&GameEvent::Won));
+        assert_eq!(engine.outcome(), Outcome::Won);
+        assert!(engine.score().time > 0);
+
+        // Nothing happens after the end
+        engine.tick(Duration::from_secs(100));
+        assert_eq!(engine.outcome(), Outcome::Won);
+        assert!(engine.drain_events().is_empty());
+    }
+
+    #[test]
+    fn running_out_of_time_loses() {
+        let mut engine = paired(2);
+
+        engine.tick(Duration::from_secs(59));
+        assert_eq!(engine.outcome(), Outcome::Playing);
+
+        engine.tick(Duration::from_secs(1));
+        assert_eq!(engine.outcome(), Outcome::Lost);
+        assert_eq!(engine.drain_events(), [GameEvent::Lost]);
+
+        engine.apply(Command::FlipCard((0, 0)));
+        assert!(!engine.card((0, 0)).unwrap().face_up);
+    }
+
+    #[test]
+    fn the_seed_decides_the_board() {
+        let board = |seed| layout(&Engine::new(6, 4, rules(), seed));
+
+        assert_eq!(board(42), board(42));
+        assert_ne!(board(42), board(43));
+        assert_eq!(Engine::new(6, 4, rules(), 42).seed(), 42);
+    }
+
+    #[test]
+    fn every_card_has_one_partner() {
+        let engine = Engine::new(5, 3, rules(), 1);
+        let mut ids: Vec<u32> = layout(&engine).into_iter().map(|(_, id)| id).collect();
+        ids.sort();
+
+        let pairs: Vec<u32> = (1..=7).flat_map(|id| [id, id]).collect();
+        assert_eq!(ids, pairs);
+        // The middle of an odd board stays empty
+        assert!(engine.card((2, 1)).is_none());
+    }
+}
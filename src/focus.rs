@@ -0,0 +1,88 @@
+use ggez::graphics;
+use ggez::graphics::{Color, DrawParam, Quad, Rect};
+use ggez::input::keyboard::KeyCode;
+
+use memory_game::engine::{BonusKind, Position};
+
+const OUTLINE_WIDTH: f32 = 4.0;
+
+// What the keyboard and the gamepad can ask for. Every screen picks the
+// ones that mean something there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    // By (columns, rows)
+    Move(i32, i32),
+    Activate,
+    Bonus(BonusKind),
+    Pause,
+    Back,
+}
+
+pub fn key_control(key: KeyCode) -> Option<Control> {
+    if let Some((dx, dy)) = direction(key) {
+        return Some(Control::Move(dx, dy));
+    }
+
+    match key {
+        KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Space => Some(Control::Activate),
+        KeyCode::Key1 | KeyCode::Numpad1 => Some(Control::Bonus(BonusKind::BonusTime)),
+        KeyCode::Key2 | KeyCode::Numpad2 => Some(Control::Bonus(BonusKind::FreezeTime)),
+        KeyCode::Key3 | KeyCode::Numpad3 => Some(Control::Bonus(BonusKind::FreeMatch)),
+        _ => None,
+    }
+}
+
+// Arrow keys and WASD
+fn direction(key: KeyCode) -> Option<(i32, i32)> {
+    match key {
+        KeyCode::Left | KeyCode::A => Some((-1, 0)),
+        KeyCode::Right | KeyCode::D => Some((1, 0)),
+        KeyCode::Up | KeyCode::W => Some((0, -1)),
+        KeyCode::Down | KeyCode::S => Some((0, 1)),
+        _ => None,
+    }
+}
+
+// The cell of the card grid that the keyboard and the gamepad work on. It
+// only shows up once one of them is used.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GridCursor {
+    pub position: Position,
+    pub visible: bool,
+}
+
+impl GridCursor {
+    pub fn move_by(&mut self, dx: i32, dy: i32, columns: u32, rows: u32) {
+        let (column, row) = self.position;
+
+        // A cursor that was not shown yet stays on its cell the first time
+        if self.visible {
+            self.position = (
+                (column as i32 + dx).clamp(0, columns.max(1) as i32 - 1) as u32,
+                (row as i32 + dy).clamp(0, rows.max(1) as i32 - 1) as u32,
+            );
+        }
+        self.visible = true;
+    }
+}
+
+// Frame drawn around whatever has the keyboard focus
+pub fn draw_outline(canvas: &mut graphics::Canvas, rect: Rect) {
+    let sides = [
+        Rect::new(rect.x, rect.y, rect.w, OUTLINE_WIDTH),
+        Rect::new(rect.x, rect.bottom() - OUTLINE_WIDTH, rect.w, OUTLINE_WIDTH),
+        Rect::new(rect.x, rect.y, OUTLINE_WIDTH, rect.h),
+        Rect::new(rect.right() - OUTLINE_WIDTH, rect.y, OUTLINE_WIDTH, rect.h),
+    ];
+
+    for side in sides {
+        canvas.draw(
+            &Quad,
+            DrawParam::default()
+                .dest([side.x, side.y])
+                .scale([side.w, side.h])
+                .color(Color::YELLOW)
+                .z(10),
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use ggez::event::{Axis, Button};
+
+use crate::focus::Control;
+use memory_game::engine::BonusKind;
+
+// How far the stick has to go to count as a push, and how far back to the
+// middle before it can push again
+const STICK_PUSH: f32 = 0.6;
+const STICK_RELEASE: f32 = 0.3;
+
+// The face buttons follow the Xbox layout: A (south) flips or confirms and
+// B (east) goes back
+pub fn button_control(button: Button) -> Option<Control> {
+    match button {
+        Button::DPadLeft => Some(Control::Move(-1, 0)),
+        Button::DPadRight => Some(Control::Move(1, 0)),
+        Button::DPadUp => Some(Control::Move(0, -1)),
+        Button::DPadDown => Some(Control::Move(0, 1)),
+        Button::South => Some(Control::Activate),
+        Button::East => Some(Control::Back),
+        Button::LeftTrigger => Some(Control::Bonus(BonusKind::BonusTime)),
+        Button::RightTrigger => Some(Control::Bonus(BonusKind::FreezeTime)),
+        Button::LeftTrigger2 | Button::RightTrigger2 => Some(Control::Bonus(BonusKind::FreeMatch)),
+        Button::Start => Some(Control::Pause),
+        _ => None,
+    }
+}
+
+// Turns the analog stick into single steps, one for every push
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stick {
+    x: i32,
+    y: i32,
+}
+
+impl Stick {
+    pub fn axis(&mut self, axis: Axis, value: f32) -> Option<Control> {
+        // Up is positive on the pad but a step up the grid
+        match axis {
+            Axis::LeftStickX => {
+                let (held, step) = stick_step(self.x, value);
+                self.x = held;
+                step.map(|step| Control::Move(step, 0))
+            }
+            Axis::LeftStickY => {
+                let (held, step) = stick_step(self.y, -value);
+                self.y = held;
+                step.map(|step| Control::Move(0, step))
+            }
+            _ => None,
+        }
+    }
+}
+
+// One direction of the stick. Takes the way it is held, -1, 0 or 1, and
+// gives the new way it is held and the step to take if any. Holding it
+// out only steps once, it has to come back to the middle first.
+fn stick_step(held: i32, value: f32) -> (i32, Option<i32>) {
+    let step = if value >= STICK_PUSH {
+        1
+    } else if value <= -STICK_PUSH {
+        -1
+    } else if value.abs() < STICK_RELEASE {
+        return (0, None);
+    } else {
+        return (held, None);
+    };
+
+    if held == step {
+        (held, None)
+    } else {
+        (step, Some(step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buttons_map_to_controls() {
+        let cases = [
+            (
+                Button::LeftTrigger,
+                Some(Control::Bonus(BonusKind::BonusTime)),
+            ),
+            (
+                Button::RightTrigger,
+                Some(Control::Bonus(BonusKind::FreezeTime)),
+            ),
+            (
+                Button::LeftTrigger2,
+                Some(Control::Bonus(BonusKind::FreeMatch)),
+            ),
+            (
+                Button::RightTrigger2,
+                Some(Control::Bonus(BonusKind::FreeMatch)),
+            ),
+            (Button::Start, Some(Control::Pause)),
+            (Button::South, Some(Control::Activate)),
+            (Button::East, Some(Control::Back)),
+            (Button::DPadUp, Some(Control::Move(0, -1))),
+            (Button::DPadRight, Some(Control::Move(1, 0))),
+            (Button::North, None),
+            (Button::Select, None),
+        ];
+
+        for (button, control) in cases {
+            assert_eq!(button_control(button), control, "{:?}", button);
+        }
+    }
+
+    #[test]
+    fn small_stick_moves_do_nothing() {
+        assert_eq!(stick_step(0, 0.2), (0, None));
+        assert_eq!(stick_step(0, -0.5), (0, None));
+        assert_eq!(stick_step(0, 0.6), (1, Some(1)));
+        assert_eq!(stick_step(0, -0.6), (-1, Some(-1)));
+    }
+
+    #[test]
+    fn held_stick_steps_once() {
+        let mut stick = Stick::default();
+        let controls: Vec<_> = [0.7, 0.9, 1.0, 0.5, 0.8]
+            .into_iter()
+            .filter_map(|value| stick.axis(Axis::LeftStickX, value))
+            .collect();
+
+        // Not back far enough at 0.5 to push again
+        assert_eq!(controls, [Control::Move(1, 0)]);
+    }
+
+    #[test]
+    fn stick_steps_again_after_the_middle() {
+        let mut stick = Stick::default();
+
+        assert_eq!(
+            stick.axis(Axis::LeftStickY, 0.8),
+            Some(Control::Move(0, -1))
+        );
+        assert_eq!(stick.axis(Axis::LeftStickY, 0.1), None);
+        assert_eq!(
+            stick.axis(Axis::LeftStickY, 0.8),
+            Some(Control::Move(0, -1))
+        );
+
+        // Straight to the other side counts as well
+        assert_eq!(
+            stick.axis(Axis::LeftStickY, -0.8),
+            Some(Control::Move(0, 1))
+        );
+        assert_eq!(stick.axis(Axis::RightStickX, 1.0), None);
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+pub const HIGH_SCORES_FILE: &str = "high_scores.json";
+const HIGH_SCORES_VERSION: u32 = 1;
+// Entries kept for every level (and seed)
+const TABLE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub level: String,
+    // Only set for games played with a fixed seed
+    pub seed: Option<u64>,
+    pub name: String,
+    pub score: i64,
+    pub seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScores {
+    version: u32,
+    #[serde(default)]
+    entries: Vec<HighScore>,
+}
+
+impl Default for HighScores {
+    fn default() -> HighScores {
+        HighScores {
+            version: HIGH_SCORES_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl HighScores {
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(HIGH_SCORES_FILE)
+    }
+
+    // A missing file is an empty table. A broken one is put aside so it does
+    // not get overwritten and the game starts with an empty table.
+    pub fn load(path: &Path) -> HighScores {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return HighScores::default(),
+            Err(e) => {
+                eprintln!("Could not read {}: {}", path.display(), e);
+                return HighScores::default();
+            }
+        };
+
+        match HighScores::parse(&source) {
+            Ok(high_scores) => high_scores,
+            Err(e) => {
+                eprintln!("Ignoring broken high scores in {}: {}", path.display(), e);
+                let _ = fs::rename(path, path.with_extension("json.bak"));
+                HighScores::default()
+            }
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<HighScores, String> {
+        let high_scores: HighScores = serde_json::from_str(source).map_err(|e| e.to_string())?;
+
+        if high_scores.version != HIGH_SCORES_VERSION {
+            return Err(format!("unsupported version {}", high_scores.version));
+        }
+
+        Ok(high_scores)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let source = serde_json::to_string_pretty(self)?;
+        fs::write(path, source)
+    }
+
+    // Best first: higher score, then the faster game
+    pub fn table(&self, level: &str, seed: Option<u64>) -> Vec<&HighScore> {
+        let mut table: Vec<&HighScore> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.level == level && entry.seed == seed)
+            .collect();
+
+        table.sort_by(|a, b| b.score.cmp(&a.score).then(a.seconds.cmp(&b.seconds)));
+        table
+    }
+
+    pub fn qualifies(&self, level: &str, seed: Option<u64>, score: i64, seconds: u64) -> bool {
+        let table = self.table(level, seed);
+
+        table.len() < TABLE_SIZE
+            || table.last().is_some_and(|worst| {
+                score > worst.score || (score == worst.score && seconds < worst.seconds)
+            })
+    }
+
+    pub fn insert(&mut self, entry: HighScore) {
+        let level = entry.level.clone();
+        let seed = entry.seed;
+        self.entries.push(entry);
+
+        // Drop whatever fell off the end of the table
+        let keep: Vec<HighScore> = self
+            .table(&level, seed)
+            .into_iter()
+            .take(TABLE_SIZE)
+            .cloned()
+            .collect();
+        self.entries
+            .retain(|entry| entry.level != level || entry.seed != seed);
+        self.entries.extend(keep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: &str, seed: Option<u64>, score: i64, seconds: u64) -> HighScore {
+        HighScore {
+            level: level.to_owned(),
+            seed,
+            name: format!("{} in {}s", score, seconds),
+            score,
+            seconds,
+        }
+    }
+
+    fn scores(table: Vec<&HighScore>) -> Vec<(i64, u64)> {
+        table
+            .into_iter()
+            .map(|entry| (entry.score, entry.seconds))
+            .collect()
+    }
+
+    // A directory of its own for every test, they run at the same time
+    fn dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "memory_game_high_scores_{}_{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn no_file_is_an_empty_table() {
+        let dir = dir("missing");
+        let high_scores = HighScores::load(&HighScores::path(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(high_scores.table("Easy", None).is_empty());
+    }
+
+    #[test]
+    fn a_broken_file_is_put_aside() {
+        let dir = dir("broken");
+        let path = HighScores::path(&dir);
+        fs::write(&path, "{\"version\": 1, \"entries\": [").unwrap();
+
+        let high_scores = HighScores::load(&path);
+        let moved = fs::read_to_string(path.with_extension("json.bak"));
+        let left = path.exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(high_scores.table("Easy", None).is_empty());
+        assert_eq!(moved.unwrap(), "{\"version\": 1, \"entries\": [");
+        assert!(!left);
+    }
+
+    #[test]
+    fn other_versions_are_not_loaded() {
+        assert_eq!(
+            HighScores::parse("{\"version\": 2, \"entries\": []}").unwrap_err(),
+            "unsupported version 2"
+        );
+        assert!(HighScores::parse("{\"version\": 1}").is_ok());
+    }
+
+    #[test]
+    fn scores_survive_a_save_and_load() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(entry("Easy", None, 300, 40));
+        high_scores.insert(entry("Easy", Some(7), 200, 50));
+
+        let dir = dir("saved");
+        let path = HighScores::path(&dir);
+        high_scores.save(&path).unwrap();
+        let loaded = HighScores::load(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.table("Easy", None), high_scores.table("Easy", None));
+        assert_eq!(
+            loaded.table("Easy", Some(7)),
+            high_scores.table("Easy", Some(7))
+        );
+    }
+
+    #[test]
+    fn better_and_faster_games_come_first() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(entry("Easy", None, 100, 30));
+        high_scores.insert(entry("Easy", None, 300, 60));
+        high_scores.insert(entry("Easy", None, 300, 45));
+        high_scores.insert(entry("Easy", None, 200, 10));
+
+        assert_eq!(
+            scores(high_scores.table("Easy", None)),
+            vec![(300, 45), (300, 60), (200, 10), (100, 30)]
+        );
+    }
+
+    #[test]
+    fn a_full_table_keeps_the_best() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as i64 {
+            assert!(high_scores.qualifies("Easy", None, score * 10, 30));
+            high_scores.insert(entry("Easy", None, score * 10, 30));
+        }
+
+        // Worse than the worst, or as good but slower
+        assert!(!high_scores.qualifies("Easy", None, 5, 10));
+        assert!(!high_scores.qualifies("Easy", None, 10, 30));
+        assert!(!high_scores.qualifies("Easy", None, 10, 40));
+        // As good but faster, or better
+        assert!(high_scores.qualifies("Easy", None, 10, 20));
+        assert!(high_scores.qualifies("Easy", None, 15, 60));
+
+        high_scores.insert(entry("Easy", None, 15, 60));
+        let table = scores(high_scores.table("Easy", None));
+        assert_eq!(table.len(), TABLE_SIZE);
+        assert_eq!(table.first(), Some(&(100, 30)));
+        assert_eq!(table.last(), Some(&(15, 60)));
+    }
+
+    #[test]
+    fn every_level_and_seed_has_its_own_table() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as i64 {
+            high_scores.insert(entry("Easy", None, score * 10, 30));
+        }
+        high_scores.insert(entry("Easy", Some(7), 5, 30));
+        high_scores.insert(entry("Easy", Some(8), 6, 30));
+        high_scores.insert(entry("Hard", None, 1, 30));
+
+        assert!(!high_scores.qualifies("Easy", None, 5, 30));
+        assert!(high_scores.qualifies("Easy", Some(7), 1, 30));
+        assert!(high_scores.qualifies("Hard", None, 0, 30));
+
+        assert_eq!(high_scores.table("Easy", None).len(), TABLE_SIZE);
+        assert_eq!(scores(high_scores.table("Easy", Some(7))), vec![(5, 30)]);
+        assert_eq!(scores(high_scores.table("Easy", Some(8))), vec![(6, 30)]);
+        assert_eq!(scores(high_scores.table("Hard", None)), vec![(1, 30)]);
+        assert!(high_scores.table("Medium", None).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::high_scores::HighScores;
+use crate::language::Language;
+use crate::level_config::LevelConfig;
+use crate::menu_button::MenuButton;
+
+// Entries shown for every level
+const SHOWN_ENTRIES: usize = 5;
+const LEVELS_PER_ROW: usize = 4;
+
+pub struct HighScoresView {
+    pub back: MenuButton,
+    language: Language,
+    screen_width: f32,
+}
+
+impl HighScoresView {
+    pub fn new(language: Language, screen_width: f32, screen_height: f32) -> HighScoresView {
+        let back = MenuButton::new(
+            language.tr("Back").to_owned(),
+            200.0,
+            70.,
+            (screen_width - 200.) / 2.,
+            screen_height - 130.,
+        );
+
+        HighScoresView {
+            back,
+            language,
+            screen_width,
+        }
+    }
+
+    pub fn draw(
+        &self,
+        canvas: &mut graphics::Canvas,
+        high_scores: &HighScores,
+        levels: &[LevelConfig],
+        seed: Option<u64>,
+    ) -> GameResult {
+        let title = match seed {
+            Some(seed) => format!(
+                "{} ({} {})",
+                self.language.tr("HIGH SCORES"),
+                self.language.tr("seed"),
+                seed
+            ),
+            None => self.language.tr("HIGH SCORES").to_owned(),
+        };
+        let mut text_title = graphics::Text::new(title);
+        text_title.set_scale(60.0);
+
+        canvas.draw(
+            &text_title,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: self.screen_width / 2.0,
+                    y: 70.0,
+                })
+                .offset(Point2 { x: 0.5, y: 0.5 })
+                .color(Color::WHITE),
+        );
+
+        let column_width = self.screen_width / LEVELS_PER_ROW as f32;
+        for (index, level) in levels.iter().enumerate() {
+            let mut lines = vec![level.name.clone()];
+            let table = high_scores.table(&level.name, seed);
+            if table.is_empty() {
+                lines.push("-".to_owned());
+            }
+            for (place, entry) in table.iter().take(SHOWN_ENTRIES).enumerate() {
+                lines.push(format!(
+                    "{}. {} {} ({:02}:{:02})",
+                    place + 1,
+                    entry.name,
+                    entry.score,
+                    entry.seconds / 60,
+                    entry.seconds % 60
+                ));
+            }
+
+            let mut text_level = graphics::Text::new(lines.join("\n"));
+            text_level.set_scale(26.0);
+
+            let column = (index % LEVELS_PER_ROW) as f32;
+            let row = (index / LEVELS_PER_ROW) as f32;
+            canvas.draw(
+                &text_level,
+                DrawParam::default()
+                    .dest(Point2 {
+                        x: column_width * column + 40.0,
+                        y: 150.0 + 230.0 * row,
+                    })
+                    .color(Color::WHITE),
+            );
+        }
+
+        self.back.draw(canvas)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+// Language of the menus. Texts are written in English in the code and
+// looked up here, anything without a translation stays English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    English,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Russian];
+
+    // Every language is named in itself
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Russian => "Русский",
+        }
+    }
+
+    pub fn tr(self, text: &'static str) -> &'static str {
+        match self {
+            Language::English => text,
+            Language::Russian => russian(text).unwrap_or(text),
+        }
+    }
+
+    // A whole sentence with {0}, {1}, ... where the values go, so every
+    // language can put them where its grammar wants them
+    pub fn tr_args(self, text: &'static str, args: &[&dyn fmt::Display]) -> String {
+        let mut sentence = self.tr(text).to_owned();
+        for (index, arg) in args.iter().enumerate() {
+            sentence = sentence.replace(&format!("{{{}}}", index), &arg.to_string());
+        }
+        sentence
+    }
+}
+
+fn russian(text: &str) -> Option<&'static str> {
+    let translation = match text {
+        // Home screen
+        "Continue" => "Продолжить",
+        "Settings" => "Настройки",
+        "High scores" => "Рекорды",
+        "Last replay" => "Повтор",
+        "player" => "игрок",
+        "players" => "игрока",
+        "Human" => "Человек",
+        "Random bot" => "Случайный бот",
+        "Easy bot" => "Лёгкий бот",
+        "Medium bot" => "Средний бот",
+        "Hard bot" => "Сложный бот",
+        "Perfect bot" => "Идеальный бот",
+        "Back" => "Назад",
+        "There is no deck \"{0}\", it needs a {1} in resources/{0}" => {
+            "Нет колоды \"{0}\", для неё нужен файл {1} в resources/{0}"
+        }
+        "The deck \"{0}\" has {1} faces, but the board needs {2} pairs" => {
+            "Рисунков в колоде \"{0}\": {1}, а для поля нужно пар: {2}"
+        }
+        // Settings
+        "SETTINGS" => "НАСТРОЙКИ",
+        "Master volume" => "Громкость",
+        "Sound effects" => "Звуки",
+        "Music volume" => "Громк. музыки",
+        "Music" => "Музыка",
+        "Mute" => "Без звука",
+        "Sounds" => "Набор звуков",
+        "Language" => "Язык",
+        "Deck" => "Колода",
+        "Animations" => "Анимация",
+        "Colours" => "Цвета",
+        "Fullscreen" => "Весь экран",
+        "On" => "Вкл",
+        "Off" => "Выкл",
+        "Level default" => "Как в уровне",
+        "pairs" => "пар",
+        "Slow" => "Медленно",
+        "Normal" => "Обычно",
+        "Fast" => "Быстро",
+        "Navy" => "Синий",
+        "Forest" => "Зелёный",
+        "Plum" => "Сливовый",
+        "Charcoal" => "Графит",
+        // High scores
+        "HIGH SCORES" => "РЕКОРДЫ",
+        "seed" => "сид",
+        // Playing
+        "Score" => "Очки",
+        "Player" => "Игрок",
+        "Seed" => "Сид",
+        "Pause" => "Пауза",
+        "PAUSED" => "ПАУЗА",
+        "Resume" => "Дальше",
+        "Restart" => "Заново",
+        "Quit to menu" => "В меню",
+        "+15 sec" => "+15 сек",
+        "Freeze time" => "Заморозка",
+        "Match hint" => "Подсказка",
+        // Results
+        "TIME OUT" => "ВРЕМЯ ВЫШЛО",
+        "You lost the game!" => "Вы проиграли!",
+        "FINISH" => "ФИНИШ",
+        "You win!" => "Вы победили!",
+        "It's a draw!" => "Ничья!",
+        "wins!" => "побеждает!",
+        "Matches" => "Пары",
+        "Combos" => "Комбо",
+        "Time left" => "Время",
+        "Mistakes" => "Ошибки",
+        "Bonuses used" => "Бонусы",
+        "New high score! Your name" => "Новый рекорд! Ваше имя",
+        "Enter to save" => "Enter - сохранить",
+        "Play again" => "Ещё раз",
+        "Change level" => "Другой уровень",
+        "Quit" => "Выход",
+        // Network games
+        "Waiting for the other players" => "Ждём других игроков",
+        "Wait for your turn" => "Дождитесь своего хода",
+        "You are" => "Вы -",
+        "(Esc to leave)" => "(Esc - выйти)",
+        "Disconnected" => "Соединение потеряно",
+        // Replays
+        "Replay" => "Повтор",
+        "Play" => "Пуск",
+        "Faster" => "Быстрее",
+        "Exit replay" => "Выйти",
+        _ => return None,
+    };
+
+    Some(translation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_go_where_the_language_puts_them() {
+        let text = "The deck \"{0}\" has {1} faces, but the board needs {2} pairs";
+
+        assert_eq!(
+            Language::English.tr_args(text, &[&"animals", &8, &12]),
+            "The deck \"animals\" has 8 faces, but the board needs 12 pairs"
+        );
+        assert_eq!(
+            Language::Russian.tr_args(text, &[&"animals", &8, &12]),
+            "Рисунков в колоде \"animals\": 8, а для поля нужно пар: 12"
+        );
+        assert_eq!(
+            Language::Russian.tr_args(
+                "There is no deck \"{0}\", it needs a {1} in resources/{0}",
+                &[&"animals", &"deck.toml"]
+            ),
+            "Нет колоды \"animals\", для неё нужен файл deck.toml в resources/animals"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use ggez::graphics::Rect;
+use ggez::mint::{Point2, Vector2};
+use ggez::Context;
+
+// Card images are 5:8 (width:height)
+const CARD_ASPECT: f32 = 1.6;
+// Gap between two cards, relative to the card width
+const CARD_GAP: f32 = 0.08;
+const MAX_CARD_WIDTH: f32 = 125.0;
+
+// Where the cards of a columns x rows grid go so that the whole grid fits
+// into the given area
+#[derive(Debug, Clone, Copy)]
+pub struct BoardLayout {
+    pub card_size: Vector2<f32>,
+    pub gap: f32,
+    pub origin: Point2<f32>,
+}
+
+impl BoardLayout {
+    pub fn new(
+        columns: u32,
+        rows: u32,
+        area_origin: Point2<f32>,
+        area_size: Vector2<f32>,
+    ) -> BoardLayout {
+        let columns = columns.max(1) as f32;
+        let rows = rows.max(1) as f32;
+
+        let fit_width = area_size.x / (columns + CARD_GAP * (columns - 1.));
+        let fit_height = area_size.y / (CARD_ASPECT * rows + CARD_GAP * (rows - 1.));
+        let card_width = fit_width.min(fit_height).min(MAX_CARD_WIDTH);
+
+        let card_size = Vector2 {
+            x: card_width,
+            y: card_width * CARD_ASPECT,
+        };
+        let gap = card_width * CARD_GAP;
+
+        let board_width = card_size.x * columns + gap * (columns - 1.);
+        let board_height = card_size.y * rows + gap * (rows - 1.);
+
+        BoardLayout {
+            card_size,
+            gap,
+            origin: Point2 {
+                x: area_origin.x + (area_size.x - board_width) / 2.,
+                y: area_origin.y + (area_size.y - board_height) / 2.,
+            },
+        }
+    }
+
+    // Top left corner of the card in the given cell
+    pub fn position(&self, column: u32, row: u32) -> Point2<f32> {
+        Point2 {
+            x: self.origin.x + (self.card_size.x + self.gap) * column as f32,
+            y: self.origin.y + (self.card_size.y + self.gap) * row as f32,
+        }
+    }
+}
+
+// Smallest area the screens are laid out for. A smaller window gets
+// everything scaled down instead.
+pub const MIN_WIDTH: f32 = 1024.0;
+pub const MIN_HEIGHT: f32 = 720.0;
+
+// The window in the coordinates everything is placed and hit-tested in.
+// These are logical pixels, so a HiDPI window does not shrink the game.
+#[derive(Debug, Clone, Copy)]
+pub struct Screen {
+    pub width: f32,
+    pub height: f32,
+    // Physical pixels per logical one
+    scale: f32,
+}
+
+impl Screen {
+    pub fn new(physical_width: f32, physical_height: f32, scale_factor: f32) -> Screen {
+        let scale = scale_factor
+            .min(physical_width / MIN_WIDTH)
+            .min(physical_height / MIN_HEIGHT)
+            .max(f32::EPSILON);
+
+        Screen {
+            width: physical_width / scale,
+            height: physical_height / scale,
+            scale,
+        }
+    }
+
+    pub fn from_context(ctx: &Context) -> Screen {
+        let (width, height) = ctx.gfx.drawable_size();
+
+        Screen::new(width, height, ctx.gfx.window().scale_factor() as f32)
+    }
+
+    // For `Canvas::set_screen_coordinates`
+    pub fn coordinates(&self) -> Rect {
+        Rect::new(0.0, 0.0, self.width, self.height)
+    }
+
+    // Mouse positions come in physical pixels
+    pub fn to_logical(&self, x: f32, y: f32) -> Point2<f32> {
+        Point2 {
+            x: x / self.scale,
+            y: y / self.scale,
+        }
+    }
+
+    // Top of the buttons along the bottom edge while playing
+    pub fn bottom_row(&self) -> f32 {
+        self.height - 100.0
+    }
+
+    // Cards fill the space between the timer and the bottom row
+    pub fn board(&self, columns: u32, rows: u32) -> BoardLayout {
+        BoardLayout::new(
+            columns,
+            rows,
+            Point2 { x: 20.0, y: 80.0 },
+            Vector2 {
+                x: self.width - 40.0,
+                y: self.bottom_row() - 100.0,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The last card ends inside the window and the board is centred
+    fn assert_fits(screen: &Screen, columns: u32, rows: u32) {
+        let layout = screen.board(columns, rows);
+        let last = layout.position(columns - 1, rows - 1);
+        let right = last.x + layout.card_size.x;
+        let bottom = last.y + layout.card_size.y;
+
+        assert!(layout.origin.x >= 20.0 - 0.01, "{:?}", layout);
+        assert!(layout.origin.y >= 80.0 - 0.01, "{:?}", layout);
+        assert!(right <= screen.width - 20.0 + 0.01, "{:?}", layout);
+        assert!(bottom <= screen.bottom_row() - 20.0 + 0.01, "{:?}", layout);
+        assert!((layout.origin.x - 20.0 - (screen.width - 20.0 - right)).abs() < 0.01);
+        assert!(layout.card_size.x <= MAX_CARD_WIDTH);
+        assert!((layout.card_size.y / layout.card_size.x - CARD_ASPECT).abs() < 0.001);
+    }
+
+    #[test]
+    fn a_tiny_window_gets_the_game_scaled_down() {
+        let screen = Screen::new(512.0, 360.0, 1.0);
+
+        assert_eq!((screen.width, screen.height), (MIN_WIDTH, MIN_HEIGHT));
+        assert_eq!(
+            screen.to_logical(256.0, 180.0),
+            Point2 { x: 512.0, y: 360.0 }
+        );
+        assert_fits(&screen, 6, 4);
+        assert_fits(&screen, 10, 8);
+    }
+
+    #[test]
+    fn a_very_wide_window_keeps_the_board_in_the_middle() {
+        let screen = Screen::new(3840.0, 720.0, 1.0);
+        assert_eq!((screen.width, screen.height), (3840.0, 720.0));
+
+        // The height decides how big the cards are
+        let layout = screen.board(4, 3);
+        assert!(layout.card_size.x < MAX_CARD_WIDTH);
+        assert_fits(&screen, 4, 3);
+        assert_fits(&screen, 20, 2);
+    }
+
+    #[test]
+    fn a_hidpi_window_lays_out_in_logical_pixels() {
+        let screen = Screen::new(2560.0, 1600.0, 2.0);
+        assert_eq!((screen.width, screen.height), (1280.0, 800.0));
+        assert_eq!(
+            screen.to_logical(200.0, 100.0),
+            Point2 { x: 100.0, y: 50.0 }
+        );
+        assert_fits(&screen, 6, 4);
+
+        // More scale than the window can take would make it smaller than
+        // the screens are laid out for
+        let screen = Screen::new(2560.0, 1600.0, 3.0);
+        assert!(screen.width >= MIN_WIDTH);
+        assert!((screen.height - MIN_HEIGHT).abs() < 0.01);
+        assert_fits(&screen, 6, 4);
+    }
+}
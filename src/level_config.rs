@@ -0,0 +1,229 @@
+use std::io::Read;
+use std::time::Duration;
+
+use ggez::{Context, GameError, GameResult};
+use serde::Deserialize;
+
+use memory_game::deck;
+use memory_game::deck::{Deck, DeckError, DEFAULT_DECK};
+use memory_game::engine::{BonusKind, Rules};
+
+pub const LEVELS_PATH: &str = "/levels.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelConfig {
+    pub name: String,
+    pub columns: u32,
+    pub rows: u32,
+    pub seconds: u64,
+    #[serde(default = "default_match_reward")]
+    pub match_reward: u64,
+    #[serde(default = "default_mismatch_penalty")]
+    pub mismatch_penalty: u64,
+    #[serde(default = "default_bonuses")]
+    pub bonuses: Vec<BonusKind>,
+    // Directory of the deck pack, see deck.rs
+    #[serde(default = "default_deck")]
+    pub deck: String,
+    // Allows an odd number of cells, the middle one is left empty
+    #[serde(default)]
+    pub odd_card: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LevelsFile {
+    level: Vec<LevelConfig>,
+}
+
+fn default_match_reward() -> u64 {
+    5
+}
+
+fn default_mismatch_penalty() -> u64 {
+    2
+}
+
+fn default_bonuses() -> Vec<BonusKind> {
+    vec![
+        BonusKind::BonusTime,
+        BonusKind::FreezeTime,
+        BonusKind::FreeMatch,
+    ]
+}
+
+fn default_deck() -> String {
+    DEFAULT_DECK.to_owned()
+}
+
+impl LevelConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.columns == 0 || self.rows == 0 {
+            return Err(format!(
+                "level \"{}\" needs at least one row and column",
+                self.name
+            ));
+        }
+
+        let cells = self
+            .columns
+            .checked_mul(self.rows)
+            .ok_or_else(|| format!("level \"{}\" has too many cards", self.name))?;
+        if cells < 2 {
+            return Err(format!("level \"{}\" needs at least two cards", self.name));
+        }
+
+        if cells % 2 == 1 && !self.odd_card {
+            return Err(format!(
+                "level \"{}\" has an odd number of cards ({}x{}), set odd_card = true to leave the middle cell empty",
+                self.name, self.columns, self.rows
+            ));
+        }
+
+        Ok(())
+    }
+
+    // An odd card out has no partner
+    pub fn pairs(&self) -> usize {
+        (self.columns as usize * self.rows as usize) / 2
+    }
+
+    // The level's own deck unless another one is picked, if it has a face
+    // for every pair
+    pub fn deck<'a>(&self, decks: &'a [Deck], picked: Option<&str>) -> Result<&'a Deck, DeckError> {
+        let deck = deck::find(decks, picked.unwrap_or(&self.deck))?;
+        deck.check(self.pairs())?;
+
+        Ok(deck)
+    }
+
+    pub fn rules(&self) -> Rules {
+        Rules {
+            duration: Duration::from_secs(self.seconds),
+            match_reward: Duration::from_secs(self.match_reward),
+            mismatch_penalty: Duration::from_secs(self.mismatch_penalty),
+            bonuses: self.bonuses.clone(),
+        }
+    }
+}
+
+pub fn parse_levels(source: &str) -> Result<Vec<LevelConfig>, String> {
+    let file: LevelsFile = toml::from_str(source).map_err(|e| e.to_string())?;
+
+    if file.level.is_empty() {
+        return Err("no levels defined".to_owned());
+    }
+
+    for (index, level) in file.level.iter().enumerate() {
+        level.validate()?;
+
+        // Saved games and high scores find their level by name
+        if file.level[..index]
+            .iter()
+            .any(|other| other.name == level.name)
+        {
+            return Err(format!("level \"{}\" is defined twice", level.name));
+        }
+    }
+
+    Ok(file.level)
+}
+
+// Reads the level list from the resources directory
+pub fn load_levels(ctx: &mut Context) -> GameResult<Vec<LevelConfig>> {
+    let mut source = String::new();
+    ctx.fs.open(LEVELS_PATH)?.read_to_string(&mut source)?;
+
+    parse_levels(&source)
+        .map_err(|e| GameError::ResourceLoadError(format!("{}: {}", LEVELS_PATH, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(extra: &str) -> Result<Vec<LevelConfig>, String> {
+        parse_levels(&format!(
+            "[[level]]\nname = \"Test\"\nseconds = 60\n{}",
+            extra
+        ))
+    }
+
+    fn decks() -> Vec<Deck> {
+        let deck = Deck::parse(
+            "small",
+            "name = \"Small\"\nback = \"back.png\"\nfaces = [\"a.png\", \"b.png\", \"c.png\"]",
+        )
+        .unwrap();
+        vec![deck]
+    }
+
+    #[test]
+    fn levels_parse_with_defaults() {
+        let levels = level("columns = 3\nrows = 2").unwrap();
+
+        assert_eq!(levels[0].pairs(), 3);
+        assert_eq!(levels[0].deck, DEFAULT_DECK);
+        assert_eq!(levels[0].rules().match_reward, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn empty_boards_are_refused() {
+        assert!(level("columns = 0\nrows = 4").is_err());
+        assert!(level("columns = 4\nrows = 0").is_err());
+        assert!(level("columns = 1\nrows = 1\nodd_card = true").is_err());
+    }
+
+    #[test]
+    fn odd_boards_need_odd_card() {
+        let error = level("columns = 3\nrows = 3").unwrap_err();
+        assert!(error.contains("odd_card"), "{}", error);
+
+        let levels = level("columns = 3\nrows = 3\nodd_card = true").unwrap();
+        assert_eq!(levels[0].pairs(), 4);
+    }
+
+    #[test]
+    fn huge_boards_are_refused() {
+        let error = level("columns = 65536\nrows = 65536").unwrap_err();
+        assert!(error.contains("too many"), "{}", error);
+    }
+
+    #[test]
+    fn level_names_are_unique() {
+        let second = |name: &str| {
+            level(&format!(
+                "columns = 2\nrows = 2\n[[level]]\nname = \"{}\"\nseconds = 30\ncolumns = 4\nrows = 4",
+                name
+            ))
+        };
+
+        assert_eq!(
+            second("Test").unwrap_err(),
+            "level \"Test\" is defined twice"
+        );
+        assert_eq!(second("Other").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn the_deck_has_to_exist_and_fit() {
+        let levels = level("columns = 3\nrows = 2\ndeck = \"small\"").unwrap();
+        let decks = decks();
+
+        assert_eq!(levels[0].deck(&decks, None).unwrap().name, "Small");
+
+        assert_eq!(
+            levels[0].deck(&decks, Some("missing")).unwrap_err(),
+            DeckError::Missing("missing".to_owned())
+        );
+
+        let levels = level("columns = 4\nrows = 2\ndeck = \"small\"").unwrap();
+        assert_eq!(
+            levels[0].deck(&decks, None).unwrap_err(),
+            DeckError::TooSmall {
+                name: "Small".to_owned(),
+                faces: 3,
+                pairs: 4
+            }
+        );
+    }
+}
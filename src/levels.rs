@@ -1,50 +1,23 @@
 use ggez::graphics;
 use ggez::graphics::Color;
-use ggez::graphics::DrawParam;
-use ggez::graphics::Quad;
-use ggez::graphics::TextLayout;
+use ggez::graphics::Rect;
 
-use ggez::{Context, GameResult};
+use ggez::GameResult;
+
+use crate::level_config::LevelConfig;
+use crate::menu_button::MenuButton;
 
 pub struct Level {
-    text: graphics::Text,
-    width: f32,
-    height: f32,
-    start_x: f32,
-    start_y: f32,
+    button: MenuButton,
     pub is_clicked: bool,
 }
 
 impl Level {
-    pub fn new(
-        _ctx: &mut Context,
-        text: String,
-        width: f32,
-        height: f32,
-        start_x: f32,
-        start_y: f32,
-    ) -> GameResult<Level> {
-        let mut res_text = graphics::Text::new(text);
-        res_text
-            .set_scale(30.)
-            .set_layout(TextLayout {
-                h_align: graphics::TextAlign::Middle,
-                v_align: graphics::TextAlign::Middle,
-            })
-            .set_bounds([start_x + (width / 2.), start_y + (height / 2.)])
-            .set_wrap(true);
-
-        Ok(Level {
-            text: res_text,
-            width,
-            height,
-            start_x,
-            start_y,
+    pub fn new(text: String, width: f32, height: f32, start_x: f32, start_y: f32) -> Level {
+        Level {
+            button: MenuButton::new(text, width, height, start_x, start_y),
             is_clicked: false,
-        })
-    }
-    pub fn update(&mut self, _ctx: &mut Context) -> GameResult {
-        Ok(())
+        }
     }
 
     pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
@@ -54,85 +27,68 @@ impl Level {
             rect_color = Color::YELLOW;
         }
 
-        canvas.draw(
-            &self.text,
-            DrawParam::default()
-                .color(Color::WHITE)
-                .dest([
-                    self.start_x + (self.width / 2.),
-                    self.start_y + (self.height / 2.),
-                ])
-                .z(5),
-        );
-        canvas.draw(
-            &Quad,
-            DrawParam::default()
-                .color(rect_color)
-                .scale([self.width, self.height])
-                .dest([
-                    self.start_x + (self.width / 2.),
-                    self.start_y + (self.height / 2.),
-                ])
-                .offset([0.5, 0.5]),
-        );
-
-        Ok(())
+        self.button.draw_in(canvas, rect_color)
     }
 
-    pub fn update_state(&mut self) -> GameResult {
-        Ok(())
-    }
-
-    pub fn is_clicked(&mut self, x: f32, y: f32) -> bool {
-        if y < self.start_y || y > self.start_y + self.height {
-            return false;
-        }
-        if x < self.start_x || x > self.start_x + self.width {
-            return false;
-        }
-
-        self.is_clicked = true;
-        return true;
+    pub fn rect(&self) -> Rect {
+        self.button.rect()
     }
 }
 
 pub struct Levels {
-    pub easy: Level,
-    pub medium: Level,
-    pub hard: Level,
+    pub levels: Vec<Level>,
 }
 
 impl Levels {
-    pub fn new(ctx: &mut Context, screen_width: f32, screen_height: f32) -> GameResult<Levels> {
-        let start_x = (screen_width - (600. + 100.)) / 2.;
-        let start_y = (screen_height - 70.) / 2.;
-
-        let easy = Level::new(ctx, "Easy".to_owned(), 200.0, 70., start_x, start_y)?;
-        let medium = Level::new(
-            ctx,
-            "Medium".to_owned(),
-            200.0,
-            70.,
-            start_x + 250.,
-            start_y,
-        )?;
-        let hard = Level::new(ctx, "Hard".to_owned(), 200.0, 70., start_x + 500., start_y)?;
-
-        Ok(Levels { easy, medium, hard })
+    // One button per configured level, at most five in a row and fewer if
+    // the screen is narrow
+    pub fn new(configs: &[LevelConfig], screen_width: f32, screen_height: f32) -> Levels {
+        let fits = ((screen_width + 50.) / 250.).floor().max(1.) as usize;
+        let per_row = configs.len().clamp(1, fits.min(5));
+        let row_count = configs.len().div_ceil(per_row);
+
+        let start_x = (screen_width - (per_row as f32 * 250. - 50.)) / 2.;
+        let start_y = (screen_height - (row_count as f32 * 120. - 50.)) / 2.;
+
+        let mut levels = Vec::new();
+        for (index, config) in configs.iter().enumerate() {
+            let column = (index % per_row) as f32;
+            let row = (index / per_row) as f32;
+
+            levels.push(Level::new(
+                config.name.clone(),
+                200.0,
+                70.,
+                start_x + 250. * column,
+                start_y + 120. * row,
+            ));
+        }
+
+        Levels { levels }
     }
 
-    pub fn update(&mut self, ctx: &mut Context) -> GameResult {
-        self.easy.update(ctx)?;
-        self.medium.update(ctx)?;
-        self.hard.update(ctx)?;
+    // Index of the level picked on the home screen
+    pub fn selected(&self) -> Option<usize> {
+        self.levels.iter().position(|level| level.is_clicked)
+    }
 
-        Ok(())
+    // Picking a level with the keyboard
+    pub fn select(&mut self, index: usize) {
+        if let Some(level) = self.levels.get_mut(index) {
+            level.is_clicked = true;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for level in self.levels.iter_mut() {
+            level.is_clicked = false;
+        }
     }
 
     pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
-        self.easy.draw(canvas)?;
-        self.medium.draw(canvas)?;
-        self.hard.draw(canvas)?;
+        for level in self.levels.iter() {
+            level.draw(canvas)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,16 @@
+// The rules, the bots, the network protocol and the decks, shared by the
+// game, the server and the examples
+
+pub mod score;
+
+pub mod engine;
+
+pub mod bot;
+
+pub mod net;
+
+pub mod deck;
+
+pub mod resources;
+
+pub mod server;
@@ -1,332 +1,1476 @@
 use std::collections::HashMap;
 use std::env;
 use std::path;
-use std::path::Path;
+use std::path::PathBuf;
 
-use bonuses::BonusState;
-use ggez::audio::SoundSource;
 use ggez::event;
-use ggez::event::MouseButton;
+use ggez::event::{Axis, Button, GamepadId, MouseButton};
 use ggez::glam::Vec2;
 use ggez::graphics;
 use ggez::graphics::Color;
-use ggez::timer;
+use ggez::graphics::Rect;
+use ggez::input::keyboard::{KeyCode, KeyInput, KeyMods};
 use std::time::Duration;
-use std::time::Instant;
 
-use ggez::conf::{Conf, WindowMode};
+use ggez::conf::{Conf, FullscreenType, WindowMode};
 use ggez::mint::Point2;
 use ggez::GameError;
 use ggez::{Context, ContextBuilder, GameResult};
-use rand::seq::SliceRandom;
 
+// Size the window opens with, it can be resized from there
 const WINDOW_WIDTH: f32 = 1600.0;
 const WINDOW_HEIGHT: f32 = 900.0;
-const CARD_WIDTH: u32 = 125;
-const CARD_HEIGHT: u32 = 200;
-
-pub mod gameTimer;
-use crate::gameTimer::GameTimer;
+const MAX_NAME_LENGTH: usize = 16;
+// Left end of the score next to the timer
+const SCORE_POSITION: Point2<f32> = Point2 { x: 200.0, y: 40.0 };
+
+// Buttons of the menus in focus order, left to right
+const PAUSE_ACTIONS: [PauseAction; 3] = [
+    PauseAction::Resume,
+    PauseAction::Restart,
+    PauseAction::QuitToMenu,
+];
+const RESULTS_ACTIONS: [ResultsAction; 3] = [
+    ResultsAction::PlayAgain,
+    ResultsAction::ChangeLevel,
+    ResultsAction::Quit,
+];
+
+pub mod game_timer;
+use crate::game_timer::GameTimer;
 
 pub mod sounds;
 use crate::sounds::Sounds;
 
+pub mod audio;
+use crate::audio::{Audio, SoundEvent};
+
+pub mod music;
+use crate::music::{Mood, Music, HURRY_TIME};
+
+pub mod sound_pack;
+use crate::sound_pack::SoundPack;
+
 pub mod card;
 use crate::card::Card;
 
 pub mod bonuses;
-use crate::bonuses::Bonuses;
+
+pub mod layout;
+use crate::layout::{Screen, MIN_HEIGHT, MIN_WIDTH};
 
 pub mod levels;
-use crate::levels::Levels;
+
+pub mod level_config;
+use crate::level_config::LevelConfig;
+
+pub mod clock;
+use crate::clock::{Clock, RealClock, ScaledClock};
+
+use memory_game::engine::{Command, Engine, GameEvent, Outcome, Position, Rules};
+
+pub mod menu_button;
+
+pub mod high_scores;
+use crate::high_scores::{HighScore, HighScores};
+
+pub mod save_game;
+use crate::save_game::SavedGame;
+
+pub mod high_scores_view;
+
+pub mod results;
+use crate::results::ResultsAction;
+
+pub mod pause_menu;
+use crate::pause_menu::PauseAction;
+
+pub mod replay;
+use crate::replay::{Replay, ReplayPlayer};
+
+use memory_game::bot::{Bot, BotLevel};
+
+pub mod focus;
+use crate::focus::{Control, GridCursor};
+
+pub mod gamepad;
+use crate::gamepad::Stick;
+
+use memory_game::net::ServerMessage;
+
+pub mod online;
+use crate::online::OnlineGame;
+
+pub mod players;
+use crate::players::MAX_PLAYERS;
+
+pub mod replay_controls;
+use crate::replay_controls::ReplayAction;
+
+use memory_game::deck;
+use memory_game::deck::{Deck, DeckError};
+
+pub mod tween;
+use crate::tween::CardAnimation;
+
+pub mod atlas;
+use crate::atlas::CardBatch;
+
+pub mod assets;
+use crate::assets::Assets;
+
+pub mod settings_view;
+use crate::settings_view::{SettingsItem, SETTINGS_ITEMS};
+
+pub mod widgets;
+use crate::widgets::Widgets;
+
+pub mod language;
+use crate::language::Language;
+
+pub mod settings;
+use crate::settings::{AnimationSpeed, ColourScheme, Settings};
 
 #[derive(Debug)]
 enum GameState {
     Home,
-    Match,
-    NotMatched,
+    HighScores,
+    Settings,
+    Paused,
     Win,
     Lost,
+    Replay,
+    Online,
     Default,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HomeItem {
+    Continue,
+    Level(usize),
+    Players,
+    Seat(usize),
+    HighScores,
+    Replay,
+    Settings,
+}
+
 struct MainState {
+    seed: Option<u64>,
+    clock: ScaledClock<Box<dyn Clock>>,
+    last_tick: Duration,
     mouse_down: bool,
     mouse_click: Option<Vec2>,
-    cards_map: HashMap<(u32, u32), Card>,
+    cards_map: HashMap<Position, Card>,
+    level_configs: Vec<LevelConfig>,
+    current_level: Option<usize>,
+    engine: Engine,
     timer: GameTimer,
-    selected: Vec<((u32, u32), u32)>,
-    time_on_last_click: Option<Duration>,
     game_state: GameState,
-    match_strike: usize,
-    bonuses: Bonuses,
-    levels: Levels,
-    sounds: Sounds,
+    screen: Screen,
+    widgets: Widgets,
+    high_scores: HighScores,
+    high_scores_path: PathBuf,
+    saved_game: Option<SavedGame>,
+    saved_game_path: PathBuf,
+    recording: Option<Replay>,
+    replay: Option<ReplayPlayer>,
+    replay_path: PathBuf,
+    has_replay: bool,
+    players: usize,
+    // Who plays every seat, `None` is a human
+    seats: [Option<BotLevel>; MAX_PLAYERS],
+    bots: Vec<Option<Bot>>,
+    online: Option<OnlineGame>,
+    decks: Vec<Deck>,
+    assets: Assets,
+    // Deck of the cards on the board
+    board_deck: Option<Deck>,
+    card_batch: Option<CardBatch>,
+    animation: CardAnimation,
+    // Why the last level did not start
+    home_status: Option<String>,
+    home_focus: Option<HomeItem>,
+    cursor: GridCursor,
+    stick: Stick,
+    // Focused button of the pause menu, the results screen or the settings
+    menu_focus: usize,
+    name_entry: Option<String>,
+    player_name: String,
+    audio: Audio,
+    sound_packs: Vec<SoundPack>,
+    settings: Settings,
+    settings_path: PathBuf,
 }
 
 impl MainState {
-    fn new(ctx: &mut Context) -> GameResult<MainState> {
-        let timer = GameTimer::new(ctx, Instant::now(), Duration::from_secs(0))?;
+    fn new(
+        ctx: &mut Context,
+        seed: Option<u64>,
+        clock: ScaledClock<Box<dyn Clock>>,
+        mut settings: Settings,
+    ) -> GameResult<MainState> {
+        let timer = GameTimer::new(Duration::from_secs(0));
 
         let cards_map = HashMap::new();
+        let engine = Engine::with_layout(0, 0, Vec::new(), Rules::default());
+
+        let level_configs = level_config::load_levels(ctx)?;
+        let screen = Screen::from_context(ctx);
+        let widgets = Widgets::new(screen, &level_configs, settings.language);
+
+        let high_scores_path = HighScores::path(ctx.fs.user_data_dir());
+        let high_scores = HighScores::load(&high_scores_path);
+        let saved_game_path = SavedGame::path(ctx.fs.user_data_dir());
+        let saved_game = SavedGame::load(&saved_game_path);
+        let replay_path = Replay::path(ctx.fs.user_data_dir());
+        let has_replay = replay_path.exists();
+        let settings_path = Settings::path(ctx.fs.user_config_dir());
+        let seats = [None; MAX_PLAYERS];
+
+        // A level that can never be played is a mistake in the config, but
+        // only that level is lost. Picking it shows why.
+        let decks = deck::discover(ctx);
+        for config in level_configs.iter() {
+            if let Err(e) = config.deck(&decks, None) {
+                eprintln!("Level \"{}\" can not be played: {}", config.name, e);
+            }
+        }
 
-        let bonuses = Bonuses::new(ctx, WINDOW_WIDTH)?;
-        let levels = Levels::new(ctx, WINDOW_WIDTH, WINDOW_HEIGHT)?;
-        let sounds = Sounds::new(ctx)?;
+        // A deck pack that has been removed since goes back to the levels'
+        if let Some(dir) = settings.deck.take() {
+            match deck::find(&decks, &dir) {
+                Ok(_) => settings.deck = Some(dir),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
 
-        Ok(MainState {
+        // The decks of the levels load in the background of the home screen
+        let mut assets = Assets::default();
+        if let Some(Ok(deck)) = settings.deck.as_ref().map(|dir| deck::find(&decks, dir)) {
+            assets.queue_deck(deck);
+        }
+        for config in level_configs.iter() {
+            if let Ok(deck) = deck::find(&decks, &config.deck) {
+                assets.queue_deck(deck);
+            }
+        }
+
+        // A sound pack that has been removed since goes back to the default
+        let sound_packs = sound_pack::discover(ctx);
+        if sound_pack::find(&sound_packs, &settings.sound_pack).is_none() {
+            eprintln!(
+                "There is no sound pack \"{}\", it needs a {} in resources/{}",
+                settings.sound_pack,
+                sound_pack::MANIFEST,
+                settings.sound_pack
+            );
+            settings.sound_pack = sound_pack::DEFAULT_PACK.to_owned();
+        }
+
+        let sounds = load_sounds(ctx, &sound_packs, &settings.sound_pack);
+        let mut audio = Audio::new(sounds, Music::discover(ctx));
+        audio.apply(&settings);
+        let animation = CardAnimation::default().scaled(settings.animations.factor());
+
+        let mut state = MainState {
+            seed,
+            last_tick: clock.now(),
+            clock,
             mouse_down: false,
             mouse_click: None,
             cards_map,
+            level_configs,
+            current_level: None,
+            engine,
             timer,
-            selected: Vec::new(),
-            time_on_last_click: None,
             game_state: GameState::Home,
-            match_strike: 0,
-            bonuses,
-            levels,
-            sounds,
-        })
+            screen,
+            widgets,
+            high_scores,
+            high_scores_path,
+            saved_game,
+            saved_game_path,
+            recording: None,
+            replay: None,
+            replay_path,
+            has_replay,
+            players: 1,
+            seats,
+            bots: Vec::new(),
+            online: None,
+            decks,
+            assets,
+            board_deck: None,
+            card_batch: None,
+            animation,
+            home_status: None,
+            home_focus: None,
+            cursor: GridCursor::default(),
+            stick: Stick::default(),
+            menu_focus: 0,
+            name_entry: None,
+            player_name: String::new(),
+            audio,
+            sound_packs,
+            settings,
+            settings_path,
+        };
+        state.refresh_labels();
+
+        Ok(state)
+    }
+
+    fn create_game(&mut self, ctx: &mut Context, level: usize) -> GameResult {
+        let config = &self.level_configs[level];
+
+        // Without a seed from the command line every game gets a fresh one
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let engine = Engine::new(config.columns, config.rows, config.rules(), seed)
+            .with_players(self.players);
+
+        let deck = self
+            .level_deck(level)
+            .map_err(|e| GameError::ResourceLoadError(e.to_string()))?
+            .dir
+            .clone();
+
+        self.start_game(ctx, level, engine, deck)
+    }
+
+    // The deck picked in the settings, or else the one of the level, if it
+    // has enough faces for the board
+    fn level_deck(&self, level: usize) -> Result<&Deck, DeckError> {
+        self.level_configs[level].deck(&self.decks, self.settings.deck.as_deref())
     }
 
-    fn create_game(&mut self, ctx: &mut Context, board_size: u32, seconds: Duration) -> GameResult {
-        let mut card_ids: Vec<u32> = (1..=(((board_size * 3) / 2) as u32))
-            .chain(1..=((board_size * 3) / 2 as u32))
+    // Starts a new or a saved game, recording it from here on
+    fn start_game(
+        &mut self,
+        ctx: &mut Context,
+        level: usize,
+        engine: Engine,
+        deck: String,
+    ) -> GameResult {
+        let name = self.level_configs[level].name.clone();
+
+        self.save_replay();
+        self.recording = Some(Replay::new(name, deck.clone(), &engine));
+
+        // Bots start with an empty memory, also in a continued game
+        self.bots = (0..engine.players())
+            .map(|seat| match self.seats[seat] {
+                Some(bot) if engine.players() > 1 => {
+                    Some(Bot::new(bot.model(), engine.seed() ^ seat as u64))
+                }
+                _ => None,
+            })
             .collect();
 
-        let mut rnd = rand::thread_rng();
-        card_ids.shuffle(&mut rnd);
-
-        self.timer = GameTimer::new(ctx, Instant::now(), seconds)?;
-
-        let start =
-            (WINDOW_WIDTH.floor() as u32 - (10 * (board_size - 1)) - (CARD_WIDTH * board_size)) / 2;
-
-        for j in 1..4 {
-            let y = (50 * j) + CARD_HEIGHT * (j - 1);
-            for i in 0..board_size {
-                let x = start + (CARD_WIDTH + 10) * i;
-
-                if let Some(match_id) = card_ids.pop() {
-                    self.cards_map.insert(
-                        (x, y),
-                        Card::new(
-                            ctx,
-                            Path::new("/cards/back.png"),
-                            &Path::new(&format!("/cards/card_{:?}.png", match_id)),
-                            Point2 {
-                                x: x as f32,
-                                y: y as f32,
-                            },
-                            match_id,
-                        )
-                        .unwrap(),
-                    );
+        self.engine = engine;
+        self.current_level = Some(level);
+        self.cursor.position = (0, 0);
+        self.clock.resume();
+        self.last_tick = self.clock.now();
+
+        self.build_cards(ctx, &deck)
+    }
+
+    // Cards are built from what the engine has on the board
+    fn build_cards(&mut self, ctx: &mut Context, deck: &str) -> GameResult {
+        self.timer = GameTimer::new(self.engine.remaining());
+
+        let layout = self.screen.board(self.engine.columns(), self.engine.rows());
+        let pairs = self.engine.cards().map(|(_, card)| card.match_id).max();
+        let deck = deck::find(&self.decks, deck)
+            .and_then(|deck| {
+                deck.check(pairs.unwrap_or(0) as usize)
+                    .map(|_| deck.clone())
+            })
+            .map_err(|e| GameError::ResourceLoadError(deck_error(&e, self.settings.language)))?;
+
+        let atlas = self.assets.atlas(ctx, &deck)?;
+        self.card_batch = Some(CardBatch::new(ctx, atlas));
+
+        self.cards_map.clear();
+        for (&(column, row), board_card) in self.engine.cards() {
+            let mut card = Card::new(
+                layout.position(column, row),
+                layout.card_size,
+                board_card.match_id,
+            );
+            card.set_label(deck.label(board_card.match_id));
+            card.is_clicked = board_card.face_up;
+
+            self.cards_map.insert((column, row), card);
+        }
+        self.board_deck = Some(deck);
+
+        Ok(())
+    }
+
+    fn join_online(&mut self, address: &str) -> GameResult {
+        let online = OnlineGame::connect(address, self.settings.language).map_err(|e| {
+            GameError::CustomError(format!("Could not connect to {}: {}", address, e))
+        })?;
+
+        self.save_replay();
+        self.bots.clear();
+        self.cards_map.clear();
+        self.current_level = None;
+        self.online = Some(online);
+        self.game_state = GameState::Online;
+
+        Ok(())
+    }
+
+    // Until a card is turned over its front is just another back
+    fn build_online_cards(
+        &mut self,
+        ctx: &mut Context,
+        columns: u32,
+        rows: u32,
+        cards: &[Position],
+        deck: &str,
+    ) -> GameResult {
+        let layout = self.screen.board(columns, rows);
+        let deck = deck::find(&self.decks, deck)
+            .and_then(|deck| deck.check(cards.len() / 2).map(|_| deck.clone()))
+            .map_err(|e| GameError::ResourceLoadError(deck_error(&e, self.settings.language)))?;
+
+        let atlas = self.assets.atlas(ctx, &deck)?;
+        self.card_batch = Some(CardBatch::new(ctx, atlas));
+
+        self.cards_map.clear();
+        for &(column, row) in cards {
+            let card = Card::new(layout.position(column, row), layout.card_size, 0);
+
+            self.cards_map.insert((column, row), card);
+        }
+        self.board_deck = Some(deck);
+
+        Ok(())
+    }
+
+    fn update_online(&mut self, ctx: &mut Context) -> GameResult {
+        let online = match self.online.as_mut() {
+            Some(online) => online,
+            None => return Ok(()),
+        };
+
+        let messages = online.poll();
+
+        if let Some(click) = self.mouse_click.take() {
+            let position = self
+                .cards_map
+                .iter()
+                .find(|(_, card)| card.contains(click.x, click.y))
+                .map(|(position, _)| *position);
+
+            // The server checks the turn again, this only saves a round trip
+            if let (Some(position), None) = (position, online.won) {
+                if online.is_my_turn() {
+                    online.flip(position);
                 } else {
-                    break;
+                    online.status =
+                        Some(self.settings.language.tr("Wait for your turn").to_owned());
                 }
             }
         }
 
+        for message in messages {
+            match message {
+                ServerMessage::Start {
+                    columns,
+                    rows,
+                    cards,
+                    deck,
+                    ..
+                } => {
+                    // A board this client has no deck for ends the game
+                    // here, not the whole program
+                    if let Err(e) = self.build_online_cards(ctx, columns, rows, &cards, &deck) {
+                        eprintln!("Could not join the game: {}", e);
+                        let message = match e {
+                            GameError::ResourceLoadError(message) => message,
+                            e => e.to_string(),
+                        };
+                        if let Some(online) = self.online.as_mut() {
+                            online.leave(message);
+                        }
+                        break;
+                    }
+                    self.audio.play(SoundEvent::LevelStart);
+                }
+                ServerMessage::Flipped { position, match_id } => {
+                    let card = self.cards_map.get_mut(&position);
+                    if let (Some(card), Some(deck)) = (card, self.board_deck.as_ref()) {
+                        card.set_front(match_id);
+                        card.set_label(deck.label(match_id));
+                        card.click();
+                    }
+                }
+                ServerMessage::Matched(first, second) => {
+                    let players = self.online.as_ref().map_or(1, |online| online.scores.len());
+                    let turn = self.online.as_ref().map_or(0, |online| online.turn);
+                    self.dismiss_pair(first, second, score_target(players, turn));
+
+                    self.audio.play(SoundEvent::Match);
+                }
+                ServerMessage::NotMatched(first, second) => {
+                    for position in [first, second] {
+                        if let Some(card) = self.cards_map.get_mut(&position) {
+                            card.click();
+                        }
+                    }
+
+                    self.audio.play(SoundEvent::Mismatch);
+                }
+                ServerMessage::GameOver { won: true, .. } => self.audio.play(SoundEvent::Win),
+                ServerMessage::GameOver { won: false, .. } => self.audio.play(SoundEvent::Lose),
+                _ => {}
+            }
+        }
+
+        if let Some(online) = self.online.as_ref() {
+            self.timer.update(online.remaining)?;
+        }
+        self.update_cards(ctx.time.delta());
+
         Ok(())
     }
-}
 
-impl event::EventHandler<ggez::GameError> for MainState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        // Slecting the game level
-        if matches!(self.game_state, GameState::Home) {
-            if self.levels.easy.is_clicked {
-                self.create_game(ctx, 2, Duration::from_secs(45))?;
-                self.game_state = GameState::Default;
-            } else if self.levels.medium.is_clicked {
-                self.create_game(ctx, 4, Duration::from_secs(60))?;
-                self.game_state = GameState::Default;
-            } else if self.levels.hard.is_clicked {
-                self.create_game(ctx, 6, Duration::from_secs(90))?;
-                self.game_state = GameState::Default;
-            } else if let Some(click) = self.mouse_click {
-                if self.levels.easy.is_clicked(click.x, click.y) {
-                    self.sounds.start.play(ctx)?;
-                    self.levels.update(ctx)?;
-                } else if self.levels.medium.is_clicked(click.x, click.y) {
-                    self.sounds.start.play(ctx)?;
-                    self.levels.update(ctx)?;
-                } else if self.levels.hard.is_clicked(click.x, click.y) {
-                    self.sounds.start.play(ctx)?;
-                    self.levels.update(ctx)?;
-                }
-            }
-
-            self.levels.update(ctx)?;
+    fn draw_online(&mut self, canvas: &mut graphics::Canvas) -> GameResult {
+        let online = match self.online.as_ref() {
+            Some(online) => online,
+            None => return Ok(()),
+        };
+
+        self.timer.draw(canvas)?;
+        players::draw_scoreboard(canvas, &online.scores, online.turn, SCORE_POSITION)?;
+
+        if let Some(batch) = self.card_batch.as_mut() {
+            batch.draw(canvas, self.cards_map.values(), &self.animation);
+        }
+        self.draw_cursor(canvas, online.columns, online.rows);
+
+        let language = self.settings.language;
+        let mut lines = Vec::new();
+        if let Some(player) = online.player {
+            lines.push(format!(
+                "{} {}",
+                language.tr("You are"),
+                players::player_name(player, language)
+            ));
+        }
+        match online.won {
+            Some(won) => {
+                let ranking = players::ranking(&online.scores);
+                let result = match ranking.as_slice() {
+                    [(_, first), (_, second), ..] if first == second => {
+                        language.tr("It's a draw!").to_owned()
+                    }
+                    [(winner, _), ..] => format!(
+                        "{} {}",
+                        players::player_name(*winner, language),
+                        language.tr("wins!")
+                    ),
+                    [] => String::new(),
+                };
+                let title = if won { "FINISH" } else { "TIME OUT" };
+                lines.push(format!(
+                    "{} {}  {}",
+                    language.tr(title),
+                    result,
+                    language.tr("(Esc to leave)")
+                ));
+            }
+            None => {
+                if let Some(status) = &online.status {
+                    lines.push(status.clone());
+                }
+            }
+        }
+
+        let mut text_status = graphics::Text::new(lines.join("\n"));
+        text_status.set_scale(30.0);
+        canvas.draw(
+            &text_status,
+            graphics::DrawParam::default()
+                .dest(Point2 {
+                    x: 20.0,
+                    y: self.screen.bottom_row(),
+                })
+                .color(match online.player {
+                    Some(player) => players::player_color(player),
+                    None => Color::WHITE,
+                }),
+        );
+
+        Ok(())
+    }
 
+    // The save stays until the game is running again, a level or a deck
+    // that is missing now may be back next time
+    fn continue_game(&mut self, ctx: &mut Context) -> GameResult {
+        let saved_game = match self.saved_game.as_ref() {
+            Some(saved_game) => saved_game,
+            None => return Ok(()),
+        };
+
+        // The level may have been removed from the config since
+        let level = match self
+            .level_configs
+            .iter()
+            .position(|config| config.name == saved_game.level)
+        {
+            Some(level) => level,
+            None => {
+                eprintln!("Saved level \"{}\" no longer exists", saved_game.level);
+                return Ok(());
+            }
+        };
+
+        let config = &self.level_configs[level];
+        if saved_game.engine.columns() != config.columns || saved_game.engine.rows() != config.rows
+        {
+            eprintln!(
+                "Saved game does not fit level \"{}\" anymore, it has a {}x{} board",
+                saved_game.level,
+                saved_game.engine.columns(),
+                saved_game.engine.rows()
+            );
             return Ok(());
         }
 
+        // The cards look like they did when the game was left
+        let deck = match saved_game.deck.as_deref() {
+            Some(dir) => deck::find(&self.decks, dir)
+                .and_then(|deck| deck.check(self.level_configs[level].pairs()).map(|_| deck)),
+            None => self.level_configs[level].deck(&self.decks, None),
+        };
+        let deck = match deck {
+            Ok(deck) => deck.dir.clone(),
+            Err(e) => {
+                self.show_home_status(&e);
+                return Ok(());
+            }
+        };
+
+        let engine = saved_game.engine.clone();
+        self.start_game(ctx, level, engine, deck)?;
+        self.saved_game = None;
+        SavedGame::delete(&self.saved_game_path);
         self.game_state = GameState::Default;
 
-        // Check if any bonus is selected
-        if self.mouse_down {
-            if let Some(click) = self.mouse_click {
-                if self.bonuses.bonus_time.click_and_update(click.x, click.y) {
-                    self.sounds.bonus.play(ctx)?;
-
-                    self.timer.duration += Duration::new(15, 0);
-                    self.mouse_down = false;
-                    self.mouse_click = None;
-                } else if self.bonuses.freeze_time.click_and_update(click.x, click.y) {
-                    self.sounds.bonus.play(ctx)?;
-
-                    self.timer.duration += Duration::new(15, 0);
-                    self.mouse_down = false;
-                    self.mouse_click = None;
-                } else if self.selected.len() <= 1
-                    && self.bonuses.free_match.click_and_update(click.x, click.y)
+        // Give the player a moment before the clock runs again
+        self.pause_action(ctx, PauseAction::Pause)
+    }
+
+    fn watch_replay(&mut self, ctx: &mut Context, replay: Replay) -> GameResult {
+        let player = ReplayPlayer::new(replay);
+
+        // Bots are in the replay only through the commands they gave
+        self.save_replay();
+        self.bots.clear();
+        self.engine = player.engine();
+        self.current_level = None;
+        self.clock.resume();
+        self.last_tick = self.clock.now();
+        self.build_cards(ctx, &player.replay.deck)?;
+
+        self.replay = Some(player);
+        self.game_state = GameState::Replay;
+
+        Ok(())
+    }
+
+    fn replay_action(&mut self, ctx: &mut Context, action: ReplayAction) -> GameResult {
+        let player = match self.replay.as_mut() {
+            Some(player) => player,
+            None => return Ok(()),
+        };
+
+        match action {
+            ReplayAction::TogglePlay => player.toggle_play(&mut self.engine),
+            ReplayAction::Faster => player.faster(),
+            ReplayAction::Seek(position) => {
+                let start = player.replay.start_time();
+                let length = player.replay.end_time().saturating_sub(start);
+                player.seek(&mut self.engine, start + length.mul_f32(position));
+
+                // Jumping around skips the events, the board is built anew
+                self.engine.drain_events();
+                let deck = player.replay.deck.clone();
+                self.build_cards(ctx, &deck)?;
+            }
+            ReplayAction::Exit => {
+                self.replay = None;
+                self.go_home();
+            }
+        }
+
+        Ok(())
+    }
+
+    // Every input goes through these two, so that it gets recorded
+    fn apply(&mut self, command: Command) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_command(&self.engine, command);
+        }
+        self.engine.apply(command);
+    }
+
+    fn tick(&mut self, dt: Duration) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_tick(&self.engine, dt);
+        }
+        self.engine.tick(dt);
+    }
+
+    // Keeps the last game (finished or not) for watching it again
+    fn save_replay(&mut self) {
+        if let Some(recording) = self.recording.take() {
+            match recording.save(&self.replay_path) {
+                Ok(()) => self.has_replay = true,
+                Err(e) => eprintln!(
+                    "Could not save the replay to {}: {}",
+                    self.replay_path.display(),
+                    e
+                ),
+            }
+        }
+    }
+
+    // Pausing stops the game clock, so the countdown, the bonuses and the
+    // flip back delay all wait for the player
+    fn pause_action(&mut self, ctx: &mut Context, action: PauseAction) -> GameResult {
+        match action {
+            PauseAction::Pause => {
+                if matches!(self.game_state, GameState::Default) {
+                    self.clock.pause();
+                    self.game_state = GameState::Paused;
+                    self.menu_focus = 0;
+                }
+            }
+            PauseAction::Resume => {
+                if matches!(self.game_state, GameState::Paused) {
+                    self.clock.resume();
+                    self.game_state = GameState::Default;
+                }
+            }
+            PauseAction::Restart => {
+                if let Some(level) = self.current_level {
+                    self.create_game(ctx, level)?;
+                    self.game_state = GameState::Default;
+                }
+            }
+            PauseAction::QuitToMenu => self.go_home(),
+        }
+
+        Ok(())
+    }
+
+    fn results_action(&mut self, ctx: &mut Context, action: ResultsAction) -> GameResult {
+        // Leaving the results screen keeps a high score that was not saved yet
+        self.save_high_score();
+
+        match action {
+            ResultsAction::PlayAgain => {
+                if let Some(level) = self.current_level {
+                    self.create_game(ctx, level)?;
+                    self.game_state = GameState::Default;
+                }
+            }
+            ResultsAction::ChangeLevel => self.go_home(),
+            ResultsAction::Quit => ctx.request_quit(),
+        }
+
+        Ok(())
+    }
+
+    fn save_high_score(&mut self) {
+        let name = match self.name_entry.take() {
+            Some(name) => name.trim().to_owned(),
+            None => return,
+        };
+        let level = match self.current_level {
+            Some(level) => &self.level_configs[level],
+            None => return,
+        };
+
+        let name = if name.is_empty() {
+            "Player".to_owned()
+        } else {
+            name
+        };
+
+        self.high_scores.insert(HighScore {
+            level: level.name.clone(),
+            seed: self.seed,
+            name: name.clone(),
+            score: self.engine.score().total(),
+            seconds: self.engine.played().as_secs(),
+        });
+        self.player_name = name;
+
+        if let Err(e) = self.high_scores.save(&self.high_scores_path) {
+            eprintln!(
+                "Could not save high scores to {}: {}",
+                self.high_scores_path.display(),
+                e
+            );
+        }
+    }
+
+    // Button texts that follow the choices made on the home screen
+    fn refresh_labels(&mut self) {
+        let language = self.settings.language;
+
+        self.widgets
+            .players_button
+            .set_text(players_label(self.players, language));
+        for (seat, button) in self.widgets.seat_buttons.iter_mut().enumerate() {
+            button.set_text(seat_label(seat, self.seats[seat], language));
+        }
+        for item in SETTINGS_ITEMS {
+            let value = self.settings_value(item);
+            self.widgets.settings_view.set_value(item, value);
+        }
+    }
+
+    // What the button of a setting says
+    fn settings_value(&self, item: SettingsItem) -> String {
+        let settings = &self.settings;
+        let tr = |text| settings.language.tr(text);
+        let on_off = |on| if on { tr("On") } else { tr("Off") }.to_owned();
+
+        match item {
+            SettingsItem::MasterVolume => format!("{}%", settings.master_volume),
+            SettingsItem::SfxVolume => format!("{}%", settings.sfx_volume),
+            SettingsItem::MusicVolume => format!("{}%", settings.music_volume),
+            SettingsItem::Music => on_off(settings.music),
+            SettingsItem::Mute => on_off(settings.muted),
+            SettingsItem::SoundPack => sound_pack::find(&self.sound_packs, &settings.sound_pack)
+                .map_or(settings.sound_pack.clone(), |pack| pack.name.clone()),
+            SettingsItem::Language => settings.language.name().to_owned(),
+            SettingsItem::Deck => {
+                deck_label(&self.decks, settings.deck.as_deref(), settings.language)
+            }
+            SettingsItem::Animations => tr(settings.animations.name()).to_owned(),
+            SettingsItem::Colours => tr(settings.colours.name()).to_owned(),
+            SettingsItem::Fullscreen => on_off(settings.fullscreen),
+            SettingsItem::Back => tr("Back").to_owned(),
+        }
+    }
+
+    // For a new window size or language
+    fn rebuild_widgets(&mut self, ctx: &mut Context) -> GameResult {
+        self.screen = Screen::from_context(ctx);
+        self.widgets = Widgets::new(self.screen, &self.level_configs, self.settings.language);
+        self.refresh_labels();
+        self.place_cards();
+
+        Ok(())
+    }
+
+    // Lays the cards out again for the current window size
+    fn place_cards(&mut self) {
+        let (columns, rows) = match &self.online {
+            Some(online) => (online.columns, online.rows),
+            None => (self.engine.columns(), self.engine.rows()),
+        };
+        let layout = self.screen.board(columns, rows);
+
+        for (&(column, row), card) in self.cards_map.iter_mut() {
+            card.set_place(layout.position(column, row), layout.card_size);
+        }
+    }
+
+    fn show_home_status(&mut self, e: &DeckError) {
+        eprintln!("{}", e);
+        self.home_status = Some(deck_error(e, self.settings.language));
+    }
+
+    // Steps a setting forwards or backwards through its values, applies it
+    // and saves all of them
+    fn settings_action(&mut self, ctx: &mut Context, item: SettingsItem, step: i32) -> GameResult {
+        let settings = &mut self.settings;
+
+        match item {
+            SettingsItem::MasterVolume => {
+                settings.master_volume = settings::step_volume(settings.master_volume, step)
+            }
+            SettingsItem::SfxVolume => {
+                settings.sfx_volume = settings::step_volume(settings.sfx_volume, step)
+            }
+            SettingsItem::MusicVolume => {
+                settings.music_volume = settings::step_volume(settings.music_volume, step)
+            }
+            SettingsItem::Music => settings.music = !settings.music,
+            SettingsItem::Mute => settings.muted = !settings.muted,
+            SettingsItem::SoundPack => {
+                let dirs: Vec<String> = self
+                    .sound_packs
+                    .iter()
+                    .map(|pack| pack.dir.clone())
+                    .collect();
+                if !dirs.is_empty() {
+                    settings.sound_pack = settings::cycle(&dirs, settings.sound_pack.clone(), step);
+                    let sounds = load_sounds(ctx, &self.sound_packs, &settings.sound_pack);
+                    self.audio.set_sounds(sounds);
+                    // A taste of the new pack
+                    self.audio.play(SoundEvent::Match);
+                }
+            }
+            SettingsItem::Language => {
+                settings.language = settings::cycle(&Language::ALL, settings.language, step);
+                self.rebuild_widgets(ctx)?;
+            }
+            SettingsItem::Deck => {
+                // The level's own deck, then every deck in turn
+                let dirs: Vec<Option<String>> = std::iter::once(None)
+                    .chain(self.decks.iter().map(|deck| Some(deck.dir.clone())))
+                    .collect();
+                settings.deck = settings::cycle(&dirs, settings.deck.clone(), step);
+                if let Some(Ok(deck)) = settings
+                    .deck
+                    .as_ref()
+                    .map(|dir| deck::find(&self.decks, dir))
                 {
-                    self.sounds.bonus.play(ctx)?;
-
-                    if self.selected.len() == 0 {
-                        let mut match_id: i32 = -1;
-                        for (key, value) in self.cards_map.iter_mut() {
-                            if match_id == -1 {
-                                match_id = value.match_id as i32;
-                                value.click();
-                                self.selected.push((*key, value.match_id));
-                                continue;
-                            }
-                            if value.match_id as i32 == match_id {
-                                self.selected.push((*key, value.match_id));
-                                self.time_on_last_click = Some(ctx.time.time_since_start());
-
-                                value.click();
-                                break;
-                            }
-                        }
-                    }
-                    if self.selected.len() == 1 {
-                        let match_id: u32 = self.selected[0].1;
-                        for (key, value) in self.cards_map.iter_mut() {
-                            if value.match_id == match_id && value.is_clicked == false {
-                                self.selected.push((*key, value.match_id));
-                                self.time_on_last_click = Some(ctx.time.time_since_start());
-
-                                value.click();
-                                self.mouse_down = false;
-                                self.mouse_click = None;
-                            }
-                        }
-                    }
-                    self.mouse_down = false;
-                    self.mouse_click = None;
+                    self.assets.queue_deck(deck);
                 }
+                self.home_status = None;
+            }
+            SettingsItem::Animations => {
+                settings.animations =
+                    settings::cycle(&AnimationSpeed::ALL, settings.animations, step);
+                self.animation = CardAnimation::default().scaled(settings.animations.factor());
+            }
+            SettingsItem::Colours => {
+                settings.colours = settings::cycle(&ColourScheme::ALL, settings.colours, step)
+            }
+            SettingsItem::Fullscreen => {
+                let fullscreen = !settings.fullscreen;
+                self.set_fullscreen(ctx, fullscreen)?
+            }
+            SettingsItem::Back => {
+                self.game_state = GameState::Home;
+                return Ok(());
             }
         }
 
-        self.bonuses.update(ctx)?;
+        self.audio.apply(&self.settings);
+        self.refresh_labels();
+        self.save_settings();
+
+        Ok(())
+    }
 
-        // Depending on the freeze time bonus update the timer
-        match self.bonuses.freeze_time.state {
-            BonusState::Used => {
-                self.timer.update(ctx)?;
-                self.sounds.bonus.play(ctx)?;
+    fn set_fullscreen(&mut self, ctx: &mut Context, fullscreen: bool) -> GameResult {
+        let mode = if fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Windowed
+        };
+        ctx.gfx.set_fullscreen(mode)?;
+        self.settings.fullscreen = fullscreen;
+
+        Ok(())
+    }
+
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save(&self.settings_path) {
+            eprintln!(
+                "Could not save the settings to {}: {}",
+                self.settings_path.display(),
+                e
+            );
+        }
+    }
+
+    fn go_home(&mut self) {
+        self.save_replay();
+        self.clock.resume();
+        self.cards_map.clear();
+        self.current_level = None;
+        self.widgets.levels.reset();
+        self.game_state = GameState::Home;
+    }
+
+    fn update_board(&mut self, dt: Duration) -> GameResult {
+        self.timer.update(self.engine.remaining())?;
+        self.widgets.bonuses.update(&self.engine)?;
+        self.update_cards(dt);
+
+        Ok(())
+    }
+
+    // Animations run on the game clock, like everything on the board
+    fn update_cards(&mut self, dt: Duration) {
+        for card in self.cards_map.values_mut() {
+            card.update(dt, &self.animation);
+        }
+        self.cards_map.retain(|_, card| !card.is_gone());
+    }
+
+    fn dismiss_pair(&mut self, first: Position, second: Position, target: Point2<f32>) {
+        for position in [first, second] {
+            if let Some(card) = self.cards_map.get_mut(&position) {
+                card.dismiss(target);
+            }
+        }
+    }
+
+    // The replay runs on the game clock, so `--speed` works here too
+    fn update_replay(&mut self, ctx: &mut Context) -> GameResult {
+        if let Some(click) = self.mouse_click.take() {
+            if let Some(action) = self.widgets.replay_controls.clicked(click.x, click.y) {
+                self.replay_action(ctx, action)?;
             }
-            BonusState::NotUsed => self.timer.update(ctx)?,
-            BonusState::Using => {}
-            BonusState::NotActive => self.timer.update(ctx)?,
         }
 
-        // Game is over if the given time passed
-        if self.timer.remaining <= Duration::new(0, 5) && self.cards_map.len() != 0 {
-            self.game_state = GameState::Lost;
-            ctx.request_quit();
+        let now = self.clock.now();
+        let dt = now.saturating_sub(self.last_tick);
+        self.last_tick = now;
 
-            return Ok(());
+        if let Some(player) = self.replay.as_mut() {
+            player.advance(&mut self.engine, dt);
         }
+        self.handle_events();
 
-        // Game if over of there are no cards left
-        if self.cards_map.len() == 0 {
-            ctx.request_quit();
-            self.game_state = GameState::Win;
-            return Ok(());
+        self.update_board(dt)
+    }
+
+    // Everything on the home screen, in keyboard focus order
+    fn home_items(&self) -> Vec<HomeItem> {
+        let mut items = Vec::new();
+
+        if self.saved_game.is_some() {
+            items.push(HomeItem::Continue);
+        }
+        items.extend((0..self.widgets.levels.levels.len()).map(HomeItem::Level));
+        items.push(HomeItem::Players);
+        // Seat buttons are only there for a game with more than one player
+        if self.players > 1 {
+            items.extend((0..self.players).map(HomeItem::Seat));
+        }
+        items.push(HomeItem::HighScores);
+        if self.has_replay {
+            items.push(HomeItem::Replay);
         }
+        items.push(HomeItem::Settings);
 
-        // Check if any card is clicked if there are 0 or 1 flipped already
-        if self.mouse_down && self.selected.len() < 2 {
-            if let Some(click) = self.mouse_click {
-                for (key, value) in self.cards_map.iter_mut() {
-                    if key.0 as f32 <= click[0] && (key.0 + CARD_WIDTH) as f32 >= click[0] {
-                        if key.1 as f32 <= click[1] && (key.1 + CARD_HEIGHT) as f32 >= click[1] {
-                            if value.is_clicked {
-                                return Ok(());
-                            }
-                            value.click();
-                            self.selected.push((*key, value.match_id));
-                            self.mouse_down = false;
-                            self.mouse_click = None;
-                            self.time_on_last_click = Some(ctx.time.time_since_start());
-
-                            return Ok(());
-                        }
-                    }
+        items
+    }
+
+    fn home_item_rect(&self, item: HomeItem) -> Rect {
+        match item {
+            HomeItem::Level(index) => self.widgets.levels.levels[index].rect(),
+            HomeItem::Continue => self.widgets.continue_button.rect(),
+            HomeItem::Players => self.widgets.players_button.rect(),
+            HomeItem::Seat(seat) => self.widgets.seat_buttons[seat].rect(),
+            HomeItem::HighScores => self.widgets.high_scores_button.rect(),
+            HomeItem::Replay => self.widgets.replay_button.rect(),
+            HomeItem::Settings => self.widgets.settings_button.rect(),
+        }
+    }
+
+    fn home_item_at(&self, x: f32, y: f32) -> Option<HomeItem> {
+        self.home_items()
+            .into_iter()
+            .find(|item| self.home_item_rect(*item).contains([x, y]))
+    }
+
+    // Clicking a button and pressing Enter on it do the same
+    fn home_action(&mut self, ctx: &mut Context, item: HomeItem) -> GameResult {
+        match item {
+            HomeItem::Level(index) => {
+                self.widgets.levels.select(index);
+                self.audio.play(SoundEvent::LevelStart);
+            }
+            HomeItem::Continue => {
+                self.audio.play(SoundEvent::LevelStart);
+                self.continue_game(ctx)?;
+            }
+            HomeItem::Players => {
+                self.players = self.players % MAX_PLAYERS + 1;
+                self.widgets
+                    .players_button
+                    .set_text(players_label(self.players, self.settings.language));
+            }
+            HomeItem::Seat(seat) => {
+                self.seats[seat] = next_seat(self.seats[seat]);
+                self.widgets.seat_buttons[seat].set_text(seat_label(
+                    seat,
+                    self.seats[seat],
+                    self.settings.language,
+                ));
+            }
+            HomeItem::HighScores => self.game_state = GameState::HighScores,
+            HomeItem::Settings => {
+                self.game_state = GameState::Settings;
+                self.menu_focus = 0;
+            }
+            HomeItem::Replay => match Replay::load(&self.replay_path) {
+                Ok(replay) => self.watch_replay(ctx, replay)?,
+                Err(e) => {
+                    eprintln!("Could not load {}: {}", self.replay_path.display(), e);
+                    self.has_replay = false;
                 }
+            },
+        }
+
+        // The focus may point past the buttons that are left
+        let items = self.home_items();
+        if self.home_focus.is_some_and(|focus| !items.contains(&focus)) {
+            self.home_focus = items.first().copied();
+        }
+
+        Ok(())
+    }
+
+    fn move_home_focus(&mut self, step: i32) {
+        let items = self.home_items();
+        let current = self
+            .home_focus
+            .and_then(|focus| items.iter().position(|item| *item == focus));
+
+        let next = match current {
+            Some(index) => (index as i32 + step).rem_euclid(items.len() as i32) as usize,
+            None => 0,
+        };
+        self.home_focus = items.get(next).copied();
+    }
+
+    // The game music hurries when time is running out
+    fn music_mood(&self) -> Mood {
+        let remaining = self.timer.remaining;
+
+        match self.game_state {
+            GameState::Home | GameState::HighScores | GameState::Settings => Mood::Home,
+            GameState::Win | GameState::Lost => Mood::Results,
+            _ if !remaining.is_zero() && remaining < HURRY_TIME => Mood::Hurry,
+            _ => Mood::Game,
+        }
+    }
+
+    fn bot_turn(&self) -> bool {
+        matches!(self.bots.get(self.engine.turn()), Some(Some(_)))
+    }
+
+    // Keyboard and gamepad input, for whatever screen is showing
+    fn control(&mut self, ctx: &mut Context, control: Control) -> GameResult {
+        match (&self.game_state, control) {
+            (GameState::Home, Control::Move(dx, dy)) => self.move_home_focus(dx + dy),
+            (GameState::Home, Control::Activate) => {
+                if let Some(item) = self.home_focus {
+                    self.home_action(ctx, item)?;
+                }
+            }
+            (GameState::HighScores, Control::Activate | Control::Back) => {
+                self.game_state = GameState::Home
             }
+            // Up and down pick a setting, left and right change it
+            (GameState::Settings, Control::Move(0, dy)) => {
+                self.menu_focus =
+                    (self.menu_focus as i32 + dy).rem_euclid(SETTINGS_ITEMS.len() as i32) as usize;
+                self.cursor.visible = true;
+            }
+            (GameState::Settings, Control::Move(dx, _)) => {
+                let item = SETTINGS_ITEMS[self.menu_focus];
+                if item != SettingsItem::Back {
+                    self.settings_action(ctx, item, dx)?;
+                }
+                self.cursor.visible = true;
+            }
+            (GameState::Settings, Control::Activate) => {
+                self.settings_action(ctx, SETTINGS_ITEMS[self.menu_focus], 1)?
+            }
+            (GameState::Settings, Control::Back) => self.game_state = GameState::Home,
+            (GameState::Paused | GameState::Win | GameState::Lost, Control::Move(dx, dy)) => {
+                self.menu_focus = (self.menu_focus as i32 + dx + dy).rem_euclid(3) as usize;
+                self.cursor.visible = true;
+            }
+            (GameState::Paused, Control::Activate) => {
+                let action = PAUSE_ACTIONS[self.menu_focus];
+                self.pause_action(ctx, action)?;
+            }
+            (GameState::Paused, Control::Pause | Control::Back) => {
+                self.pause_action(ctx, PauseAction::Resume)?
+            }
+            (GameState::Win | GameState::Lost, Control::Activate) => {
+                let action = RESULTS_ACTIONS[self.menu_focus];
+                self.results_action(ctx, action)?;
+            }
+            (GameState::Replay, Control::Activate | Control::Pause) => {
+                self.replay_action(ctx, ReplayAction::TogglePlay)?
+            }
+            (GameState::Replay, Control::Back) => self.replay_action(ctx, ReplayAction::Exit)?,
+            (GameState::Default, Control::Pause) => self.pause_action(ctx, PauseAction::Pause)?,
+            (GameState::Default | GameState::Online, Control::Move(dx, dy)) => {
+                let (columns, rows) = match &self.online {
+                    Some(online) => (online.columns, online.rows),
+                    None => (self.engine.columns(), self.engine.rows()),
+                };
+                self.cursor.move_by(dx, dy, columns, rows);
+            }
+            (GameState::Default | GameState::Online, Control::Activate) => {
+                self.cursor.visible = true;
+                self.play(Command::FlipCard(self.cursor.position));
+            }
+            (GameState::Default, Control::Bonus(kind)) => self.play(Command::UseBonus(kind)),
+            _ => {}
         }
 
-        for (_, value) in self.cards_map.iter_mut() {
-            value.update(ctx)?;
+        Ok(())
+    }
+
+    // A move from the keyboard or the gamepad, the server decides in a
+    // network game
+    fn play(&mut self, command: Command) {
+        if let Some(online) = self.online.as_mut() {
+            if let (Command::FlipCard(position), None) = (command, online.won) {
+                online.flip(position);
+            }
+        } else if !self.bot_turn() {
+            self.apply(command);
         }
+    }
 
-        // Give some time for the card to flip before checking for match
-        if let Some(time) = self.time_on_last_click {
-            if time + Duration::from_secs(1) < ctx.time.time_since_start() {
-                // Check for match
-                if self.selected.len() == 2 && self.selected[0].1 == self.selected[1].1 {
-                    self.cards_map.remove(&self.selected[0].0);
+    fn draw_cards(&mut self, canvas: &mut graphics::Canvas) {
+        if let Some(batch) = self.card_batch.as_mut() {
+            batch.draw(canvas, self.cards_map.values(), &self.animation);
+        }
+    }
 
-                    self.cards_map.remove(&self.selected[1].0);
+    fn draw_cursor(&self, canvas: &mut graphics::Canvas, columns: u32, rows: u32) {
+        if !self.cursor.visible {
+            return;
+        }
 
-                    self.game_state = GameState::Match;
+        let layout = self.screen.board(columns, rows);
+        let (column, row) = self.cursor.position;
+        let position = layout.position(column, row);
+        let margin = layout.gap / 2.;
+
+        focus::draw_outline(
+            canvas,
+            Rect::new(
+                position.x - margin,
+                position.y - margin,
+                layout.card_size.x + 2. * margin,
+                layout.card_size.y + 2. * margin,
+            ),
+        );
+    }
 
-                    if !matches!(self.bonuses.freeze_time.state, BonusState::Using) {
-                        self.timer.give_additional_time(Duration::from_secs(5));
-                    }
+    fn card_at(&self, x: f32, y: f32) -> Option<Position> {
+        self.cards_map
+            .iter()
+            .find(|(_, card)| card.contains(x, y))
+            .map(|(position, _)| *position)
+    }
 
-                    self.match_strike += 1;
+    // Let the ggez side catch up with what happened in the rules engine
+    fn handle_events(&mut self) {
+        for event in self.engine.drain_events() {
+            for bot in self.bots.iter_mut().flatten() {
+                bot.observe(&event);
+            }
 
-                    self.selected = Vec::new();
-                } else if self.selected.len() >= 2 && self.selected[0].1 != self.selected[1].1 {
-                    let card_1 = self.cards_map.get_mut(&self.selected[0].0);
-                    if let Some(card) = card_1 {
+            match event {
+                GameEvent::CardFlipped(position, _) => {
+                    if let Some(card) = self.cards_map.get_mut(&position) {
                         card.click();
                     }
+                }
+                GameEvent::Matched(first, second) => {
+                    // A match keeps the turn, so the points went to whoever has it
+                    let target = score_target(self.engine.players(), self.engine.turn());
+                    self.dismiss_pair(first, second, target);
 
-                    let card_2 = self.cards_map.get_mut(&self.selected[1].0);
-                    if let Some(card) = card_2 {
-                        card.click();
+                    self.audio.play(SoundEvent::Match);
+                }
+                GameEvent::NotMatched(first, second) => {
+                    for position in [first, second] {
+                        if let Some(card) = self.cards_map.get_mut(&position) {
+                            card.click();
+                        }
                     }
 
-                    self.game_state = GameState::NotMatched;
+                    self.audio.play(SoundEvent::Mismatch);
+                }
+                GameEvent::BonusActivated(_) => {
+                    self.audio.play(SoundEvent::BonusActivated);
+                }
+                GameEvent::BonusUnlocked(_)
+                | GameEvent::BonusExpired(_)
+                | GameEvent::TurnChanged(_) => {}
+                // A replay stays on the board when the game is over
+                GameEvent::Won | GameEvent::Lost if self.replay.is_some() => {}
+                GameEvent::Won => {
+                    self.save_replay();
+                    self.game_state = GameState::Win;
+                    self.menu_focus = 0;
+                    self.audio.play(SoundEvent::Win);
+
+                    // Ask for a name only if the score makes it into the table.
+                    // Hot-seat games are ranked on the results screen instead.
+                    if let (Some(level), 1) = (self.current_level, self.engine.players()) {
+                        if self.high_scores.qualifies(
+                            &self.level_configs[level].name,
+                            self.seed,
+                            self.engine.score().total(),
+                            self.engine.played().as_secs(),
+                        ) {
+                            self.name_entry = Some(self.player_name.clone());
+                        }
+                    }
+                }
+                GameEvent::Lost => {
+                    self.save_replay();
+                    self.game_state = GameState::Lost;
+                    self.menu_focus = 0;
+                    self.audio.play(SoundEvent::Lose);
+                }
+            }
+        }
+    }
 
-                    if !matches!(self.bonuses.freeze_time.state, BonusState::Using) {
-                        self.timer.take_time(Duration::from_secs(2));
+    fn update_screen(&mut self, ctx: &mut Context) -> GameResult {
+        // Slecting the game level
+        if matches!(self.game_state, GameState::Home) {
+            if let Some(level) = self.widgets.levels.selected() {
+                match self.level_deck(level) {
+                    Ok(_) => {
+                        self.home_status = None;
+                        self.create_game(ctx, level)?;
+                        self.game_state = GameState::Default;
                     }
+                    Err(e) => {
+                        self.show_home_status(&e);
+                        self.widgets.levels.reset();
+                    }
+                }
+            } else if let Some(click) = self.mouse_click.take() {
+                if let Some(item) = self.home_item_at(click.x, click.y) {
+                    self.home_action(ctx, item)?;
+                }
+            }
+
+            self.assets.preload(ctx);
+
+            // The click that picked the level must not flip a card
+            if !matches!(self.game_state, GameState::Home) {
+                self.mouse_click = None;
+            }
 
-                    self.match_strike = 0;
+            return Ok(());
+        }
+
+        if matches!(self.game_state, GameState::HighScores) {
+            if let Some(click) = self.mouse_click.take() {
+                if self
+                    .widgets
+                    .high_scores_view
+                    .back
+                    .is_clicked(click.x, click.y)
+                {
+                    self.game_state = GameState::Home;
+                }
+            }
+
+            return Ok(());
+        }
 
-                    self.selected = Vec::new();
+        if matches!(self.game_state, GameState::Settings) {
+            if let Some(click) = self.mouse_click.take() {
+                if let Some(item) = self.widgets.settings_view.clicked(click.x, click.y) {
+                    self.settings_action(ctx, item, 1)?;
                 }
             }
+
+            return Ok(());
         }
 
-        // Check for match strike
-        if self.match_strike == 2 {
-            println!("strike");
-            if matches!(self.bonuses.bonus_time.state, BonusState::NotActive) {
-                self.bonuses.bonus_time.update_state()?;
+        if matches!(self.game_state, GameState::Win | GameState::Lost) {
+            if let Some(click) = self.mouse_click.take() {
+                if let Some(action) = self.widgets.results.clicked(click.x, click.y) {
+                    self.results_action(ctx, action)?;
+                }
             }
+
+            return Ok(());
         }
 
-        Ok(())
+        if matches!(self.game_state, GameState::Replay) {
+            return self.update_replay(ctx);
+        }
+
+        if matches!(self.game_state, GameState::Online) {
+            return self.update_online(ctx);
+        }
+
+        if matches!(self.game_state, GameState::Paused) {
+            if let Some(click) = self.mouse_click.take() {
+                if let Some(action) = self.widgets.pause_menu.clicked(click.x, click.y) {
+                    self.pause_action(ctx, action)?;
+                }
+            }
+
+            return Ok(());
+        }
+
+        if self.engine.outcome() != Outcome::Playing {
+            return Ok(());
+        }
+
+        let bot_turn = self.bot_turn();
+
+        // A click either pauses, uses a bonus or flips a card. While a bot
+        // plays only pausing is left to the humans.
+        if self.mouse_down {
+            if let Some(click) = self.mouse_click.take() {
+                if self.widgets.pause_menu.pause.is_clicked(click.x, click.y) {
+                    return self.pause_action(ctx, PauseAction::Pause);
+                }
+
+                if !bot_turn {
+                    if let Some(kind) = self.widgets.bonuses.clicked(click.x, click.y) {
+                        self.apply(Command::UseBonus(kind));
+                    } else if let Some(position) = self.card_at(click.x, click.y) {
+                        self.apply(Command::FlipCard(position));
+                    }
+                }
+            }
+        }
+
+        // Bots play with the same commands as a click
+        if let Some(Some(bot)) = self.bots.get_mut(self.engine.turn()) {
+            if let Some(command) = bot.think(&self.engine) {
+                self.apply(command);
+            }
+        }
+
+        // The engine only sees time passing through the game clock
+        let now = self.clock.now();
+        let dt = now.saturating_sub(self.last_tick);
+        self.tick(dt);
+        self.last_tick = now;
+        self.handle_events();
+
+        self.update_board(dt)
+    }
+}
+
+impl event::EventHandler<ggez::GameError> for MainState {
+    // Sounds asked for during the frame, also from input events, play at
+    // the end of it
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        self.update_screen(ctx)?;
+        self.audio.set_mood(self.music_mood());
+        self.audio.flush(ctx)
     }
 
     fn mouse_button_down_event(
@@ -336,12 +1480,132 @@ impl event::EventHandler<ggez::GameError> for MainState {
         x: f32,
         y: f32,
     ) -> Result<(), GameError> {
+        // Everything is placed and hit-tested in logical pixels
+        let position = self.screen.to_logical(x, y);
+
         self.mouse_down = true;
-        self.mouse_click = Some(Vec2::new(x, y));
+        self.mouse_click = Some(Vec2::new(position.x, position.y));
+        self.cursor.visible = false;
 
         Ok(())
     }
 
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        input: KeyInput,
+        _repeated: bool,
+    ) -> Result<(), GameError> {
+        match (&self.game_state, input.keycode) {
+            (_, Some(KeyCode::F11)) => {
+                self.set_fullscreen(ctx, !self.settings.fullscreen)?;
+                self.refresh_labels();
+                self.save_settings();
+            }
+            (GameState::Home, Some(KeyCode::Escape)) => ctx.request_quit(),
+            (GameState::Home, Some(KeyCode::Tab)) => {
+                let step = if input.mods.contains(KeyMods::SHIFT) {
+                    -1
+                } else {
+                    1
+                };
+                self.move_home_focus(step)
+            }
+
+            (GameState::HighScores | GameState::Settings, Some(KeyCode::Escape)) => {
+                self.game_state = GameState::Home
+            }
+            (GameState::Win, Some(KeyCode::Back)) => {
+                if let Some(name) = self.name_entry.as_mut() {
+                    name.pop();
+                }
+            }
+            (GameState::Win, Some(KeyCode::Return | KeyCode::NumpadEnter))
+                if self.name_entry.is_some() =>
+            {
+                self.save_high_score()
+            }
+            (GameState::Default, Some(KeyCode::Escape | KeyCode::P)) => {
+                self.pause_action(ctx, PauseAction::Pause)?
+            }
+            (GameState::Paused, Some(KeyCode::Escape | KeyCode::P)) => {
+                self.pause_action(ctx, PauseAction::Resume)?
+            }
+            (GameState::Replay, Some(KeyCode::Space | KeyCode::P)) => {
+                self.replay_action(ctx, ReplayAction::TogglePlay)?
+            }
+            (GameState::Replay, Some(KeyCode::F)) => {
+                self.replay_action(ctx, ReplayAction::Faster)?
+            }
+            (GameState::Online, Some(KeyCode::Escape)) => {
+                // Dropping the connection is leaving the game
+                self.online = None;
+                self.go_home();
+            }
+            (GameState::Replay, Some(KeyCode::Escape)) => {
+                self.replay_action(ctx, ReplayAction::Exit)?
+            }
+            // Letters typed into the name are no controls
+            (_, Some(key)) if self.name_entry.is_none() => {
+                if let Some(control) = focus::key_control(key) {
+                    self.control(ctx, control)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: Button,
+        _id: GamepadId,
+    ) -> Result<(), GameError> {
+        // Start also pauses and resumes, and A saves a typed in name
+        match (&self.game_state, gamepad::button_control(button)) {
+            (GameState::Win, Some(Control::Activate)) if self.name_entry.is_some() => {
+                self.save_high_score();
+                Ok(())
+            }
+            (GameState::Paused, Some(Control::Pause)) => {
+                self.pause_action(ctx, PauseAction::Resume)
+            }
+            (_, Some(control)) => self.control(ctx, control),
+            (_, None) => Ok(()),
+        }
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        ctx: &mut Context,
+        axis: Axis,
+        value: f32,
+        _id: GamepadId,
+    ) -> Result<(), GameError> {
+        match self.stick.axis(axis, value) {
+            Some(control) => self.control(ctx, control),
+            None => Ok(()),
+        }
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> Result<(), GameError> {
+        if let Some(name) = self.name_entry.as_mut() {
+            if !character.is_control() && name.chars().count() < MAX_NAME_LENGTH {
+                name.push(character);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Also comes after switching to or from fullscreen and after the window
+    // moved to a screen with another scale factor
+    fn resize_event(&mut self, ctx: &mut Context, _width: f32, _height: f32) -> GameResult {
+        self.rebuild_widgets(ctx)
+    }
+
     fn mouse_button_up_event(
         &mut self,
         _ctx: &mut Context,
@@ -355,103 +1619,111 @@ impl event::EventHandler<ggez::GameError> for MainState {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let canvas_color = graphics::Color::from_rgb(0, 25, 51);
+        self.assets.paint(ctx)?;
+
+        let canvas_color = self.settings.colours.background();
         let mut canvas = graphics::Canvas::from_frame(ctx, canvas_color);
+        canvas.set_screen_coordinates(self.screen.coordinates());
 
         // Check and draw the game state if needed
         match self.game_state {
             GameState::Home => {
-                self.levels.draw(&mut canvas)?;
+                self.widgets.levels.draw(&mut canvas)?;
+                self.widgets.high_scores_button.draw(&mut canvas)?;
+                self.widgets.players_button.draw(&mut canvas)?;
+                if self.players > 1 {
+                    for button in &self.widgets.seat_buttons[..self.players] {
+                        button.draw(&mut canvas)?;
+                    }
+                }
+                if self.saved_game.is_some() {
+                    self.widgets.continue_button.draw(&mut canvas)?;
+                }
+                if self.has_replay {
+                    self.widgets.replay_button.draw(&mut canvas)?;
+                }
+                self.widgets.settings_button.draw(&mut canvas)?;
+                if let Some(status) = &self.home_status {
+                    let mut text_status = graphics::Text::new(status.as_str());
+                    text_status.set_scale(30.0);
+                    canvas.draw(
+                        &text_status,
+                        graphics::DrawParam::default()
+                            .dest(Point2 {
+                                x: self.screen.width / 2.0,
+                                y: 160.0,
+                            })
+                            .offset(Point2 { x: 0.5, y: 0.5 })
+                            .color(Color::RED),
+                    );
+                }
+                if let Some(item) = self.home_focus {
+                    focus::draw_outline(&mut canvas, self.home_item_rect(item));
+                }
                 canvas.finish(ctx)?;
 
                 return Ok(());
             }
-            GameState::Match => {
-                self.sounds.collect.play(ctx)?;
+            GameState::HighScores => {
+                self.widgets.high_scores_view.draw(
+                    &mut canvas,
+                    &self.high_scores,
+                    &self.level_configs,
+                    self.seed,
+                )?;
+                canvas.finish(ctx)?;
 
-                println!("Match")
+                return Ok(());
             }
-            GameState::NotMatched => {
-                self.sounds.wrong.play(ctx)?;
+            GameState::Settings => {
+                self.widgets.settings_view.draw(&mut canvas)?;
+                if self.cursor.visible {
+                    let button = self
+                        .widgets
+                        .settings_view
+                        .button(SETTINGS_ITEMS[self.menu_focus]);
+                    focus::draw_outline(&mut canvas, button.rect());
+                }
+                canvas.finish(ctx)?;
 
-                println!("Not a match");
+                return Ok(());
             }
-            GameState::Win => {
-                let dest = Point2 {
-                    x: ((WINDOW_WIDTH / 2.0) as f32),
-                    y: ((WINDOW_HEIGHT / 2.0) as f32),
-                };
-
-                self.sounds.start.play_later()?;
-
-                let mut text_finish = graphics::Text::new("FINISH");
-
-                let mut text_win = graphics::Text::new("You win!");
-
-                text_finish.set_scale(70.0);
-
-                text_win.set_scale(70.0);
-
-                let draw_params_finish = graphics::DrawParam::default()
-                    .dest(Point2 {
-                        x: dest.x,
-                        y: dest.y - 40.0,
-                    })
-                    .offset(Point2 { x: 0.5, y: 0.5 })
-                    .color(Color::WHITE);
-
-                let draw_params_win = graphics::DrawParam::default()
-                    .dest(Point2 {
-                        x: dest.x,
-                        y: dest.y + 40.0,
-                    })
-                    .offset(Point2 { x: 0.5, y: 0.5 })
-                    .color(Color::WHITE);
-
-                canvas.draw(&text_finish, draw_params_finish);
-
-                canvas.draw(&text_win, draw_params_win);
-
+            GameState::Paused => {
+                // The cards stay hidden while paused
+                self.timer.draw(&mut canvas)?;
+                self.widgets.pause_menu.draw(&mut canvas)?;
+                if self.cursor.visible {
+                    let button = self
+                        .widgets
+                        .pause_menu
+                        .button(PAUSE_ACTIONS[self.menu_focus]);
+                    focus::draw_outline(&mut canvas, button.rect());
+                }
                 canvas.finish(ctx)?;
 
                 return Ok(());
             }
-            GameState::Lost => {
-                let dest = Point2 {
-                    x: ((WINDOW_WIDTH / 2.0) as f32),
-                    y: ((WINDOW_HEIGHT / 2.0) as f32),
-                };
-
-                self.sounds.fail.play_later()?;
-
-                let mut text_time_out = graphics::Text::new("TIME OUT");
-
-                let mut text_you_lost = graphics::Text::new("You lost the game!");
-
-                text_time_out.set_scale(70.0);
-
-                text_you_lost.set_scale(70.0);
-
-                let draw_params_time_out = graphics::DrawParam::default()
-                    .dest(Point2 {
-                        x: dest.x,
-                        y: dest.y - 40.0,
-                    })
-                    .offset(Point2 { x: 0.5, y: 0.5 })
-                    .color(Color::WHITE);
-
-                let draw_params_lost = graphics::DrawParam::default()
-                    .dest(Point2 {
-                        x: dest.x,
-                        y: dest.y + 40.0,
-                    })
-                    .offset(Point2 { x: 0.5, y: 0.5 })
-                    .color(Color::WHITE);
-
-                canvas.draw(&text_time_out, draw_params_time_out);
-
-                canvas.draw(&text_you_lost, draw_params_lost);
+            GameState::Online => {
+                self.draw_online(&mut canvas)?;
+                canvas.finish(ctx)?;
 
+                return Ok(());
+            }
+            GameState::Win | GameState::Lost => {
+                self.widgets.results.draw(
+                    &mut canvas,
+                    self.engine.outcome(),
+                    self.engine.score(),
+                    self.engine.player_scores(),
+                    self.name_entry.as_deref(),
+                )?;
+                if self.cursor.visible {
+                    let button = self
+                        .widgets
+                        .results
+                        .button(RESULTS_ACTIONS[self.menu_focus]);
+                    focus::draw_outline(&mut canvas, button.rect());
+                }
                 canvas.finish(ctx)?;
 
                 return Ok(());
@@ -462,12 +1734,60 @@ impl event::EventHandler<ggez::GameError> for MainState {
         //Draw timer
         self.timer.draw(&mut canvas)?;
 
+        // Draw the live score next to the timer
+        if self.engine.players() > 1 {
+            let totals: Vec<i64> = self
+                .engine
+                .player_scores()
+                .iter()
+                .map(|score| score.total())
+                .collect();
+            players::draw_scoreboard(&mut canvas, &totals, self.engine.turn(), SCORE_POSITION)?;
+        } else {
+            let mut text_score = graphics::Text::new(format!(
+                "{}: {}",
+                self.settings.language.tr("Score"),
+                self.engine.score().total()
+            ));
+            text_score.set_scale(36.0);
+            canvas.draw(
+                &text_score,
+                graphics::DrawParam::default()
+                    .dest(SCORE_POSITION)
+                    .offset(Point2 { x: 0.0, y: 0.5 })
+                    .color(Color::WHITE),
+            );
+        }
+
+        // Draw the seed so the board can be reproduced
+        let mut text_seed = graphics::Text::new(format!(
+            "{}: {}",
+            self.settings.language.tr("Seed"),
+            self.engine.seed()
+        ));
+        text_seed.set_scale(24.0);
+        canvas.draw(
+            &text_seed,
+            graphics::DrawParam::default()
+                .dest(Point2 {
+                    x: self.screen.width - 40.0,
+                    y: 40.0,
+                })
+                .offset(Point2 { x: 1.0, y: 0.5 })
+                .color(Color::WHITE),
+        );
+
         // Draw bonus buttons
-        self.bonuses.draw(&mut canvas)?;
+        self.widgets.bonuses.draw(&mut canvas)?;
+        match &self.replay {
+            Some(player) => self.widgets.replay_controls.draw(&mut canvas, player)?,
+            None => self.widgets.pause_menu.pause.draw(&mut canvas)?,
+        }
 
         // Draw cards
-        for (_key, value) in self.cards_map.iter_mut() {
-            value.draw(&mut canvas)?;
+        self.draw_cards(&mut canvas);
+        if self.replay.is_none() {
+            self.draw_cursor(&mut canvas, self.engine.columns(), self.engine.rows());
         }
 
         canvas.finish(ctx)?;
@@ -475,19 +1795,170 @@ impl event::EventHandler<ggez::GameError> for MainState {
         Ok(())
     }
 
+    // An unfinished game is saved so it can be continued next time
     fn quit_event(&mut self, _ctx: &mut Context) -> Result<bool, GameError> {
-        // Check if game quit is because Time Out or Win or sth else
-        if self.timer.remaining <= Duration::new(0, 3) || self.cards_map.len() == 0 {
-            timer::sleep(Duration::from_secs(5));
+        self.save_replay();
+
+        let in_game = matches!(self.game_state, GameState::Default | GameState::Paused);
+
+        if let (true, Some(level), Some(deck)) =
+            (in_game, self.current_level, self.board_deck.as_ref())
+        {
+            if self.engine.outcome() == Outcome::Playing {
+                let saved_game = SavedGame::new(
+                    self.level_configs[level].name.clone(),
+                    deck.dir.clone(),
+                    self.engine.clone(),
+                );
+
+                if let Err(e) = saved_game.save(&self.saved_game_path) {
+                    eprintln!(
+                        "Could not save the game to {}: {}",
+                        self.saved_game_path.display(),
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+fn load_sounds(ctx: &mut Context, packs: &[SoundPack], dir: &str) -> Sounds {
+    Sounds::new(
+        ctx,
+        sound_pack::find(packs, dir),
+        sound_pack::find(packs, sound_pack::DEFAULT_PACK),
+    )
+}
+
+// The reason a deck can not be played, for the screen
+fn deck_error(e: &DeckError, language: Language) -> String {
+    match e {
+        DeckError::Missing(dir) => language.tr_args(
+            "There is no deck \"{0}\", it needs a {1} in resources/{0}",
+            &[dir, &deck::MANIFEST],
+        ),
+        DeckError::TooSmall { name, faces, pairs } => language.tr_args(
+            "The deck \"{0}\" has {1} faces, but the board needs {2} pairs",
+            &[name, faces, pairs],
+        ),
+    }
+}
+
+fn deck_label(decks: &[Deck], deck: Option<&str>, language: Language) -> String {
+    match deck.and_then(|dir| deck::find(decks, dir).ok()) {
+        Some(deck) => format!("{} ({} {})", deck.name, deck.pairs(), language.tr("pairs")),
+        None => language.tr("Level default").to_owned(),
+    }
+}
+
+// Where matched cards fly to, the score of the player who got them
+fn score_target(players: usize, player: usize) -> Point2<f32> {
+    let position = if players > 1 {
+        players::scoreboard_position(SCORE_POSITION, player)
+    } else {
+        SCORE_POSITION
+    };
+
+    Point2 {
+        x: position.x + 60.0,
+        y: position.y,
+    }
+}
+
+fn players_label(players: usize, language: Language) -> String {
+    match players {
+        1 => format!("1 {}", language.tr("player")),
+        _ => format!("{} {}", players, language.tr("players")),
+    }
+}
+
+fn seat_label(seat: usize, kind: Option<BotLevel>, language: Language) -> String {
+    let kind = kind.map_or("Human", BotLevel::name);
+
+    format!("P{}: {}", seat + 1, language.tr(kind))
+}
+
+// Human, then every bot from the weakest to the strongest
+fn next_seat(kind: Option<BotLevel>) -> Option<BotLevel> {
+    match kind {
+        None => Some(BotLevel::ALL[0]),
+        Some(bot) => BotLevel::ALL
+            .iter()
+            .position(|level| *level == bot)
+            .and_then(|index| BotLevel::ALL.get(index + 1))
+            .copied(),
+    }
+}
+
+struct Options {
+    seed: Option<u64>,
+    speed: f64,
+    replay: Option<PathBuf>,
+    connect: Option<String>,
+}
+
+// Reads `--seed <number>`, `--speed <factor>`, `--replay <file>` and
+// `--connect <host:port>` (or `--flag=value`) from the command line
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        seed: None,
+        speed: 1.0,
+        replay: None,
+        connect: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_owned(), Some(value.to_owned())),
+            None => (arg, None),
+        };
+
+        if !["--seed", "--speed", "--replay", "--connect"].contains(&flag.as_str()) {
+            continue;
+        }
+
+        let value = match value {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| format!("{} expects a value", flag))?,
+        };
+
+        if flag == "--connect" {
+            options.connect = Some(value);
+        } else if flag == "--replay" {
+            options.replay = Some(PathBuf::from(value));
+        } else if flag == "--seed" {
+            options.seed = Some(
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("invalid seed: {}", value))?,
+            );
+        } else {
+            options.speed = value
+                .parse::<f64>()
+                .ok()
+                .filter(|speed| *speed > 0.0)
+                .ok_or_else(|| format!("invalid speed: {}", value))?;
         }
-        return Ok(false);
     }
+
+    Ok(options)
 }
+
 pub fn main() -> GameResult {
     // Конфигурация:
     let conf = Conf::new().window_mode(WindowMode {
         width: WINDOW_WIDTH,
         height: WINDOW_HEIGHT,
+        min_width: MIN_WIDTH,
+        min_height: MIN_HEIGHT,
+        resizable: true,
+        // A HiDPI screen gets a window of the same logical size
+        resize_on_scale_factor_change: true,
         ..Default::default()
     });
 
@@ -504,7 +1975,48 @@ pub fn main() -> GameResult {
         ctx.fs.mount(&path, true);
     }
 
-    let state = MainState::new(&mut ctx).unwrap();
+    let options = match parse_args(env::args()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+
+    let clock: Box<dyn Clock> = Box::new(RealClock::new());
+    let clock = ScaledClock::new(clock, options.speed);
+
+    // Applied before anything is built, the window opens in fullscreen
+    // straight away
+    let settings = Settings::load(&Settings::path(ctx.fs.user_config_dir()));
+    if settings.fullscreen {
+        ctx.gfx.set_fullscreen(FullscreenType::Desktop)?;
+    }
+
+    let mut state = match MainState::new(&mut ctx, options.seed, clock, settings) {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Could not start the game: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(address) = options.connect {
+        if let Err(e) = state.join_online(&address) {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    }
+
+    if let Some(path) = options.replay {
+        match Replay::load(&path) {
+            Ok(replay) => state.watch_replay(&mut ctx, replay)?,
+            Err(e) => {
+                eprintln!("Could not load {}: {}", path.display(), e);
+                std::process::exit(2);
+            }
+        }
+    }
 
     event::run(ctx, event_loop, state);
 }
@@ -0,0 +1,94 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::graphics::Quad;
+use ggez::graphics::Rect;
+use ggez::graphics::TextLayout;
+
+use ggez::GameResult;
+
+// A labelled box on the screen. Levels and bonuses are buttons that draw
+// themselves in the colour of their state.
+#[derive(Debug)]
+pub struct MenuButton {
+    text: graphics::Text,
+    width: f32,
+    height: f32,
+    start_x: f32,
+    start_y: f32,
+}
+
+impl MenuButton {
+    pub fn new(text: String, width: f32, height: f32, start_x: f32, start_y: f32) -> MenuButton {
+        MenuButton {
+            text: MenuButton::label(text, width, height, start_x, start_y),
+            width,
+            height,
+            start_x,
+            start_y,
+        }
+    }
+
+    fn label(text: String, width: f32, height: f32, start_x: f32, start_y: f32) -> graphics::Text {
+        let mut res_text = graphics::Text::new(text);
+        res_text
+            .set_scale(30.)
+            .set_layout(TextLayout {
+                h_align: graphics::TextAlign::Middle,
+                v_align: graphics::TextAlign::Middle,
+            })
+            .set_bounds([start_x + (width / 2.), start_y + (height / 2.)])
+            .set_wrap(true);
+
+        res_text
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = MenuButton::label(text, self.width, self.height, self.start_x, self.start_y);
+    }
+
+    pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
+        self.draw_in(canvas, Color::BLUE)
+    }
+
+    pub fn draw_in(&self, canvas: &mut graphics::Canvas, color: Color) -> GameResult {
+        canvas.draw(
+            &self.text,
+            DrawParam::default()
+                .color(Color::WHITE)
+                .dest([
+                    self.start_x + (self.width / 2.),
+                    self.start_y + (self.height / 2.),
+                ])
+                .z(5),
+        );
+        canvas.draw(
+            &Quad,
+            DrawParam::default()
+                .color(color)
+                .scale([self.width, self.height])
+                .dest([
+                    self.start_x + (self.width / 2.),
+                    self.start_y + (self.height / 2.),
+                ])
+                .offset([0.5, 0.5]),
+        );
+
+        Ok(())
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect::new(self.start_x, self.start_y, self.width, self.height)
+    }
+
+    pub fn is_clicked(&self, x: f32, y: f32) -> bool {
+        if y < self.start_y || y > self.start_y + self.height {
+            return false;
+        }
+        if x < self.start_x || x > self.start_x + self.width {
+            return false;
+        }
+
+        true
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ggez::audio;
+use ggez::audio::SoundSource;
+use ggez::Context;
+
+pub const MUSIC_DIR: &str = "/music";
+const EXTENSIONS: [&str; 3] = ["ogg", "wav", "flac"];
+// How long one track takes to fade into the next
+const CROSSFADE: Duration = Duration::from_millis(1500);
+// Remaining time from which the game music gets more urgent
+pub const HURRY_TIME: Duration = Duration::from_secs(15);
+// Without tracks of its own, hurrying plays the game tracks faster
+const HURRY_PITCH: f32 = 1.15;
+
+// What the music should sound like. The tracks of a mood are the files in
+// the music directory whose names start with its prefix, e.g. `game.ogg`
+// and `game_2.flac`. They take turns in the order of their names, a single
+// track loops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mood {
+    Home,
+    Game,
+    Hurry,
+    Results,
+}
+
+impl Mood {
+    const ALL: [Mood; 4] = [Mood::Home, Mood::Game, Mood::Hurry, Mood::Results];
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Mood::Home => "home",
+            Mood::Game => "game",
+            Mood::Hurry => "hurry",
+            Mood::Results => "results",
+        }
+    }
+}
+
+struct Track {
+    mood: Mood,
+    source: audio::Source,
+    // From 0 (silent) to 1
+    fade: f32,
+}
+
+// Looping background music that crossfades whenever the mood changes
+#[derive(Default)]
+pub struct Music {
+    playlists: HashMap<Mood, Vec<PathBuf>>,
+    // Where every playlist goes on
+    next: HashMap<Mood, usize>,
+    mood: Option<Mood>,
+    current: Option<Track>,
+    // Tracks on their way out
+    fading: Vec<Track>,
+}
+
+impl Music {
+    // Finds the tracks in the music directory, there may be none at all
+    pub fn discover(ctx: &mut Context) -> Music {
+        let mut music = Music::default();
+        if !ctx.fs.is_dir(MUSIC_DIR) {
+            return music;
+        }
+
+        let mut paths: Vec<PathBuf> = match ctx.fs.read_dir(MUSIC_DIR) {
+            Ok(entries) => entries.collect(),
+            Err(e) => {
+                eprintln!("Could not look for music: {}", e);
+                return music;
+            }
+        };
+        paths.sort();
+        paths.dedup();
+
+        for path in paths {
+            let playable = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+            let name = path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .unwrap_or("");
+            let mood = Mood::ALL
+                .into_iter()
+                .find(|mood| name.starts_with(mood.prefix()));
+
+            // Anything that is no sound file may well be a note to the reader
+            match (mood, playable) {
+                (Some(mood), true) => music.playlists.entry(mood).or_default().push(path),
+                (None, true) => eprintln!(
+                    "Ignoring {}, music files are named home, game, hurry or results",
+                    path.display()
+                ),
+                (_, false) => {}
+            }
+        }
+
+        music
+    }
+
+    // `None` fades the music out
+    pub fn set_mood(&mut self, mood: Option<Mood>) {
+        self.mood = mood;
+    }
+
+    // Once a frame, `gain` is the volume of the music from 0 to 1
+    pub fn update(&mut self, ctx: &mut Context, gain: f32) {
+        let step = ctx.time.delta().as_secs_f32() / CROSSFADE.as_secs_f32();
+
+        // A new mood or the end of a track brings on the next one
+        let finished = self
+            .current
+            .as_ref()
+            .is_some_and(|track| track.source.stopped());
+        let moved = self.current.as_ref().map(|track| track.mood) != self.mood;
+        if finished || moved {
+            if let Some(track) = self.current.take() {
+                self.fading.push(track);
+            }
+            if let Some(mood) = self.mood {
+                self.current = self.start(ctx, mood);
+            }
+        }
+
+        if let Some(track) = self.current.as_mut() {
+            track.fade = (track.fade + step).min(1.0);
+            track.source.set_volume(track.fade * gain);
+        }
+        for track in self.fading.iter_mut() {
+            track.fade = (track.fade - step).max(0.0);
+            track.source.set_volume(track.fade * gain);
+        }
+        // Dropping a source stops it
+        self.fading.retain(|track| track.fade > 0.0);
+    }
+
+    // The next track of a mood, a broken file is left out from then on
+    fn start(&mut self, ctx: &mut Context, mood: Mood) -> Option<Track> {
+        let (playlist, pitch) = if self.playlists.contains_key(&mood) {
+            (mood, 1.0)
+        } else if mood == Mood::Hurry {
+            (Mood::Game, HURRY_PITCH)
+        } else {
+            return None;
+        };
+
+        loop {
+            let paths = self.playlists.get_mut(&playlist)?;
+            if paths.is_empty() {
+                return None;
+            }
+            let index = self.next.get(&playlist).copied().unwrap_or(0) % paths.len();
+            let path = paths[index].clone();
+
+            let repeat = paths.len() == 1;
+            let source = audio::Source::new(ctx, &path).and_then(|mut source| {
+                source.set_repeat(repeat);
+                source.set_pitch(pitch);
+                source.set_volume(0.0);
+                source.play(ctx)?;
+                Ok(source)
+            });
+            match source {
+                Ok(source) => {
+                    self.next.insert(playlist, index + 1);
+                    return Some(Track {
+                        mood,
+                        source,
+                        fade: 0.0,
+                    });
+                }
+                Err(e) => {
+                    eprintln!("Could not play {}: {}", path.display(), e);
+                    paths.remove(index);
+                }
+            }
+        }
+    }
+}
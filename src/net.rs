@@ -0,0 +1,86 @@
+use std::io;
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{GameEvent, Position};
+
+pub const DEFAULT_PORT: u16 = 7878;
+
+// Messages are JSON, one per line
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    Flip(Position),
+}
+
+// Nothing in here may carry the match id of a card that is face down
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    Welcome {
+        player: usize,
+        players: usize,
+    },
+    // Only where the cards are, not what they are
+    Start {
+        columns: u32,
+        rows: u32,
+        cards: Vec<Position>,
+        deck: String,
+        race: bool,
+    },
+    Flipped {
+        position: Position,
+        match_id: u32,
+    },
+    Matched(Position, Position),
+    NotMatched(Position, Position),
+    Turn(usize),
+    State {
+        remaining: Duration,
+        scores: Vec<i64>,
+    },
+    GameOver {
+        won: bool,
+        scores: Vec<i64>,
+    },
+    Rejected(String),
+}
+
+impl ServerMessage {
+    // What the clients get to know about an engine event. A match id only
+    // goes out with the flip that turns the card face up.
+    pub fn from_event(event: &GameEvent) -> Option<ServerMessage> {
+        match *event {
+            GameEvent::CardFlipped(position, match_id) => {
+                Some(ServerMessage::Flipped { position, match_id })
+            }
+            GameEvent::Matched(first, second) => Some(ServerMessage::Matched(first, second)),
+            GameEvent::NotMatched(first, second) => Some(ServerMessage::NotMatched(first, second)),
+            GameEvent::TurnChanged(player) => Some(ServerMessage::Turn(player)),
+            _ => None,
+        }
+    }
+}
+
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> io::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+
+    writer.write_all(line.as_bytes())?;
+    writer.flush()
+}
+
+// `None` once the other side closed the connection
+pub fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> io::Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
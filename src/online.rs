@@ -0,0 +1,150 @@
+use std::io;
+use std::io::BufReader;
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use crate::language::Language;
+use memory_game::engine::Position;
+use memory_game::net;
+use memory_game::net::{ClientMessage, ServerMessage};
+
+// The client side of a network game. The server decides everything, this
+// only keeps what it was told to draw the board.
+pub struct OnlineGame {
+    stream: TcpStream,
+    receiver: Receiver<ServerMessage>,
+    pub player: Option<usize>,
+    pub race: bool,
+    pub columns: u32,
+    pub rows: u32,
+    pub turn: usize,
+    pub scores: Vec<i64>,
+    pub remaining: Duration,
+    // Set once the server says the game is over, true for a cleared board
+    pub won: Option<bool>,
+    pub connected: bool,
+    // Last thing worth telling the player, like a rejected flip
+    pub status: Option<String>,
+    language: Language,
+}
+
+impl OnlineGame {
+    pub fn connect(address: &str, language: Language) -> io::Result<OnlineGame> {
+        let stream = TcpStream::connect(address)?;
+        let reader = stream.try_clone()?;
+
+        // Messages are read on their own thread so a frame never waits for
+        // the network
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+
+            while let Ok(Some(message)) = net::receive(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(OnlineGame {
+            stream,
+            receiver,
+            player: None,
+            race: false,
+            columns: 0,
+            rows: 0,
+            turn: 0,
+            scores: Vec::new(),
+            remaining: Duration::ZERO,
+            won: None,
+            connected: true,
+            status: Some(language.tr("Waiting for the other players").to_owned()),
+            language,
+        })
+    }
+
+    pub fn flip(&mut self, position: Position) {
+        if let Err(e) = net::send(&mut self.stream, &ClientMessage::Flip(position)) {
+            self.disconnect(e.to_string());
+        }
+    }
+
+    // Messages that arrived since the last frame. The ones that only change
+    // the state shown around the board are handled here already.
+    pub fn poll(&mut self) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(message) => {
+                    self.handle(&message);
+                    messages.push(message);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.connected && self.won.is_none() {
+                        self.disconnect("the server closed the connection".to_owned());
+                    }
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+
+        messages
+    }
+
+    fn handle(&mut self, message: &ServerMessage) {
+        match message {
+            ServerMessage::Welcome { player, players } => {
+                self.player = Some(*player);
+                self.scores = vec![0; *players];
+            }
+            ServerMessage::Start {
+                columns,
+                rows,
+                race,
+                ..
+            } => {
+                self.columns = *columns;
+                self.rows = *rows;
+                self.race = *race;
+                self.status = None;
+            }
+            ServerMessage::Turn(player) => self.turn = *player,
+            ServerMessage::State { remaining, scores } => {
+                self.remaining = *remaining;
+                self.scores = scores.clone();
+            }
+            ServerMessage::GameOver { won, scores } => {
+                self.won = Some(*won);
+                self.scores = scores.clone();
+            }
+            ServerMessage::Rejected(reason) => self.status = Some(reason.clone()),
+            ServerMessage::Flipped { .. }
+            | ServerMessage::Matched(..)
+            | ServerMessage::NotMatched(..) => {}
+        }
+    }
+
+    fn disconnect(&mut self, reason: String) {
+        eprintln!("Lost the connection: {}", reason);
+        self.connected = false;
+        self.status = Some(format!("{}: {}", self.language.tr("Disconnected"), reason));
+    }
+
+    // For a game this client cannot show. The server sees the connection
+    // close like for anyone else who leaves.
+    pub fn leave(&mut self, reason: String) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+        self.connected = false;
+        self.status = Some(reason);
+    }
+
+    pub fn is_my_turn(&self) -> bool {
+        self.race || self.player == Some(self.turn)
+    }
+}
@@ -0,0 +1,123 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::language::Language;
+use crate::menu_button::MenuButton;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseAction {
+    Pause,
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+
+pub struct PauseMenu {
+    pub pause: MenuButton,
+    pub resume: MenuButton,
+    pub restart: MenuButton,
+    pub quit: MenuButton,
+    language: Language,
+    screen_width: f32,
+    screen_height: f32,
+}
+
+impl PauseMenu {
+    pub fn new(
+        language: Language,
+        screen_width: f32,
+        screen_height: f32,
+        bottom_row: f32,
+    ) -> PauseMenu {
+        let start_x = (screen_width - (600. + 100.)) / 2.;
+        let start_y = (screen_height - 70.) / 2.;
+
+        // Shown in the corner of the board while playing
+        let pause = MenuButton::new(
+            language.tr("Pause").to_owned(),
+            200.0,
+            70.,
+            screen_width - 240.,
+            bottom_row,
+        );
+
+        let resume = MenuButton::new(
+            language.tr("Resume").to_owned(),
+            200.0,
+            70.,
+            start_x,
+            start_y,
+        );
+        let restart = MenuButton::new(
+            language.tr("Restart").to_owned(),
+            200.0,
+            70.,
+            start_x + 250.,
+            start_y,
+        );
+        let quit = MenuButton::new(
+            language.tr("Quit to menu").to_owned(),
+            200.0,
+            70.,
+            start_x + 500.,
+            start_y,
+        );
+
+        PauseMenu {
+            pause,
+            resume,
+            restart,
+            quit,
+            language,
+            screen_width,
+            screen_height,
+        }
+    }
+
+    pub fn button(&self, action: PauseAction) -> &MenuButton {
+        match action {
+            PauseAction::Pause => &self.pause,
+            PauseAction::Resume => &self.resume,
+            PauseAction::Restart => &self.restart,
+            PauseAction::QuitToMenu => &self.quit,
+        }
+    }
+
+    pub fn clicked(&self, x: f32, y: f32) -> Option<PauseAction> {
+        if self.resume.is_clicked(x, y) {
+            Some(PauseAction::Resume)
+        } else if self.restart.is_clicked(x, y) {
+            Some(PauseAction::Restart)
+        } else if self.quit.is_clicked(x, y) {
+            Some(PauseAction::QuitToMenu)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
+        let mut text_paused = graphics::Text::new(self.language.tr("PAUSED"));
+        text_paused.set_scale(70.0);
+
+        canvas.draw(
+            &text_paused,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: self.screen_width / 2.0,
+                    y: self.screen_height / 2.0 - 120.0,
+                })
+                .offset(Point2 { x: 0.5, y: 0.5 })
+                .color(Color::WHITE),
+        );
+
+        self.resume.draw(canvas)?;
+        self.restart.draw(canvas)?;
+        self.quit.draw(canvas)?;
+
+        Ok(())
+    }
+}
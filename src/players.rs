@@ -0,0 +1,86 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::language::Language;
+
+pub const MAX_PLAYERS: usize = 4;
+// Room for one player on the scoreboard
+const ENTRY_WIDTH: f32 = 170.0;
+
+const PLAYER_COLORS: [Color; MAX_PLAYERS] = [
+    Color {
+        r: 1.0,
+        g: 0.4,
+        b: 0.4,
+        a: 1.0,
+    },
+    Color {
+        r: 0.4,
+        g: 0.7,
+        b: 1.0,
+        a: 1.0,
+    },
+    Color {
+        r: 0.5,
+        g: 1.0,
+        b: 0.5,
+        a: 1.0,
+    },
+    Color {
+        r: 1.0,
+        g: 0.85,
+        b: 0.3,
+        a: 1.0,
+    },
+];
+
+pub fn player_name(player: usize, language: Language) -> String {
+    format!("{} {}", language.tr("Player"), player + 1)
+}
+
+pub fn player_color(player: usize) -> Color {
+    PLAYER_COLORS[player % MAX_PLAYERS]
+}
+
+// Best first, players with the same score stay in turn order
+pub fn ranking(totals: &[i64]) -> Vec<(usize, i64)> {
+    let mut ranking: Vec<(usize, i64)> = totals.iter().copied().enumerate().collect();
+    ranking.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+    ranking
+}
+
+// Left end of a player's entry on the scoreboard
+pub fn scoreboard_position(origin: Point2<f32>, player: usize) -> Point2<f32> {
+    Point2 {
+        x: origin.x + ENTRY_WIDTH * player as f32,
+        y: origin.y,
+    }
+}
+
+// Score of every player next to the timer, the one whose turn it is marked
+pub fn draw_scoreboard(
+    canvas: &mut graphics::Canvas,
+    scores: &[i64],
+    turn: usize,
+    origin: Point2<f32>,
+) -> GameResult {
+    for (player, score) in scores.iter().enumerate() {
+        let marker = if player == turn { "> " } else { "  " };
+        let mut text_player = graphics::Text::new(format!("{}P{}: {}", marker, player + 1, score));
+        text_player.set_scale(if player == turn { 34.0 } else { 28.0 });
+
+        canvas.draw(
+            &text_player,
+            DrawParam::default()
+                .dest(scoreboard_position(origin, player))
+                .offset(Point2 { x: 0.0, y: 0.5 })
+                .color(player_color(player)),
+        );
+    }
+
+    Ok(())
+}
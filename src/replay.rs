@@ -0,0 +1,352 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::save_game::check_engine;
+use memory_game::engine::{Command, Engine, Outcome};
+
+pub const REPLAY_FILE: &str = "replay.json";
+const REPLAY_VERSION: u32 = 1;
+// Speeds the fast forward button goes through
+const SPEEDS: [f64; 4] = [1.0, 2.0, 4.0, 8.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    // The engine clock moved up to the time of the event
+    Tick,
+    Command(Command),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    // Game time, the same as `Engine::played`
+    pub time: Duration,
+    pub input: Input,
+}
+
+// Everything the engine was fed during one game. The engine state from the
+// start (with the seed of the board) is kept too, so continued games replay
+// just as well as new ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    version: u32,
+    pub level: String,
+    pub deck: String,
+    pub start: Engine,
+    #[serde(default)]
+    pub events: Vec<ReplayEvent>,
+    // The last tick can take in the next one
+    #[serde(skip)]
+    open_tick: bool,
+}
+
+impl Replay {
+    pub fn new(level: String, deck: String, start: &Engine) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            level,
+            deck,
+            start: start.clone(),
+            events: Vec::new(),
+            open_tick: false,
+        }
+    }
+
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(REPLAY_FILE)
+    }
+
+    // Both have to be recorded before the input reaches the engine
+    pub fn record_command(&mut self, engine: &Engine, command: Command) {
+        self.push(engine, engine.played(), Input::Command(command));
+        self.open_tick = false;
+    }
+
+    // Frames where nothing happens by itself add up to one tick. A frame in
+    // which something is due stays a tick of its own, so it happens at the
+    // same time in the replay.
+    pub fn record_tick(&mut self, engine: &Engine, dt: Duration) {
+        let time = engine.played() + dt;
+        let quiet = !acts(engine, time);
+
+        match self.events.last_mut() {
+            Some(last) if self.open_tick && quiet && engine.outcome() == Outcome::Playing => {
+                last.time = time;
+            }
+            _ => self.push(engine, time, Input::Tick),
+        }
+        self.open_tick = quiet;
+    }
+
+    fn push(&mut self, engine: &Engine, time: Duration, input: Input) {
+        // The engine ignores everything once the game is over
+        if engine.outcome() == Outcome::Playing {
+            self.events.push(ReplayEvent { time, input });
+        }
+    }
+
+    // Game time of the first and the last input
+    pub fn start_time(&self) -> Duration {
+        self.start.played()
+    }
+
+    pub fn end_time(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(self.start_time(), |event| event.time)
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+        Replay::parse(&source)
+    }
+
+    pub fn parse(source: &str) -> Result<Replay, String> {
+        let replay: Replay = serde_json::from_str(source).map_err(|e| e.to_string())?;
+
+        if replay.version != REPLAY_VERSION {
+            return Err(format!("unsupported version {}", replay.version));
+        }
+        check_engine(&replay.start)?;
+
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let source = serde_json::to_string(self)?;
+        fs::write(path, source)
+    }
+}
+
+// Whether ticking the engine up to `time` does anything by itself
+fn acts(engine: &Engine, time: Duration) -> bool {
+    engine.next_due().is_some_and(|due| due < time)
+}
+
+// Feeds a replay back into an engine, at any speed and from any point
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    next: usize,
+    time: Duration,
+    speed: usize,
+    playing: bool,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> ReplayPlayer {
+        ReplayPlayer {
+            time: replay.start_time(),
+            replay,
+            next: 0,
+            speed: 0,
+            playing: true,
+        }
+    }
+
+    pub fn engine(&self) -> Engine {
+        self.replay.start.clone()
+    }
+
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    pub fn speed(&self) -> f64 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+
+    // Playing again after the end starts over
+    pub fn toggle_play(&mut self, engine: &mut Engine) {
+        if self.is_finished() && !self.playing {
+            self.seek(engine, self.replay.start_time());
+        }
+        self.playing = !self.playing;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1) % SPEEDS.len();
+    }
+
+    // Moves the playback on by `dt` of real time
+    pub fn advance(&mut self, engine: &mut Engine, dt: Duration) {
+        if self.playing {
+            self.run_until(engine, self.time + dt.mul_f64(self.speed()));
+        }
+    }
+
+    // The engine only goes forward, so going back starts over from the start
+    // state
+    pub fn seek(&mut self, engine: &mut Engine, time: Duration) {
+        if time < self.time {
+            *engine = self.engine();
+            self.next = 0;
+            self.time = self.replay.start_time();
+        }
+
+        self.run_until(engine, time);
+    }
+
+    fn run_until(&mut self, engine: &mut Engine, time: Duration) {
+        while let Some(event) = self.replay.events.get(self.next) {
+            if event.time > time {
+                break;
+            }
+
+            match event.input {
+                Input::Tick => engine.tick(event.time.saturating_sub(engine.played())),
+                Input::Command(command) => engine.apply(command),
+            }
+            self.next += 1;
+        }
+
+        // A long quiet tick is played bit by bit, so the timer keeps going.
+        // One where something is due waits for its end.
+        let next = self.replay.events.get(self.next);
+        if next.is_some_and(|event| event.input == Input::Tick) && !acts(engine, time) {
+            engine.tick(time.saturating_sub(engine.played()));
+        }
+
+        self.time = time.min(self.replay.end_time()).max(self.time);
+        if self.is_finished() {
+            self.playing = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use memory_game::engine::{BonusKind, GameEvent, Position, Rules};
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    fn start() -> Engine {
+        let rules = Rules {
+            duration: Duration::from_secs(60),
+            ..Rules::default()
+        };
+        Engine::new(4, 3, rules, 3)
+    }
+
+    fn pairs(engine: &Engine) -> Vec<(Position, Position)> {
+        (1..=6)
+            .map(|match_id| {
+                let mut cards: Vec<Position> = engine
+                    .cards()
+                    .filter(|(_, card)| card.match_id == match_id)
+                    .map(|(position, _)| *position)
+                    .collect();
+                cards.sort();
+                (cards[0], cards[1])
+            })
+            .collect()
+    }
+
+    // A whole game with a mismatch, both time bonuses and a free match, in
+    // frames like the game plays it. Gives the recording and what the engine
+    // said on the way.
+    fn record() -> (Replay, Vec<GameEvent>, Engine) {
+        let mut engine = start();
+        let pairs = pairs(&engine);
+        let mut script = vec![
+            (30, Command::FlipCard(pairs[0].0)),
+            (40, Command::FlipCard(pairs[1].0)),
+            (150, Command::UseBonus(BonusKind::FreezeTime)),
+            (200, Command::FlipCard(pairs[0].0)),
+            (210, Command::FlipCard(pairs[0].1)),
+            (300, Command::UseBonus(BonusKind::FreeMatch)),
+            // Two matches in a row unlocked it
+            (400, Command::UseBonus(BonusKind::BonusTime)),
+        ];
+        // Pairs the free match took are gone, those flips do nothing
+        for (index, (first, second)) in pairs.iter().enumerate().skip(1) {
+            let frame = 500 + 200 * index as u32;
+            script.push((frame, Command::FlipCard(*first)));
+            script.push((frame + 10, Command::FlipCard(*second)));
+        }
+
+        let mut replay = Replay::new("Test".to_owned(), "cards".to_owned(), &engine);
+        let mut events = Vec::new();
+        for frame in 0..3000 {
+            for (_, command) in script.iter().filter(|(at, _)| *at == frame) {
+                replay.record_command(&engine, *command);
+                engine.apply(*command);
+            }
+            replay.record_tick(&engine, FRAME);
+            engine.tick(FRAME);
+            events.extend(engine.drain_events());
+        }
+
+        (replay, events, engine)
+    }
+
+    #[test]
+    fn quiet_frames_make_one_tick() {
+        let (replay, events, engine) = record();
+
+        assert_eq!(engine.outcome(), Outcome::Won);
+        for kind in [
+            BonusKind::FreezeTime,
+            BonusKind::FreeMatch,
+            BonusKind::BonusTime,
+        ] {
+            assert!(
+                events.contains(&GameEvent::BonusExpired(kind)),
+                "{:?}",
+                kind
+            );
+        }
+        assert!(replay.events.len() < 60, "{} inputs", replay.events.len());
+    }
+
+    #[test]
+    fn replay_says_what_the_game_said() {
+        let (replay, recorded, original) = record();
+        let replay = Replay::parse(&serde_json::to_string(&replay).unwrap()).unwrap();
+
+        // Another frame rate and speed than the recording
+        let mut player = ReplayPlayer::new(replay);
+        player.faster();
+        let mut engine = player.engine();
+        let mut replayed = Vec::new();
+        while !player.is_finished() {
+            player.advance(&mut engine, Duration::from_millis(35));
+            replayed.extend(engine.drain_events());
+        }
+
+        assert_eq!(replayed, recorded);
+        assert_eq!(engine.score(), original.score());
+        assert_eq!(engine.remaining(), original.remaining());
+    }
+
+    #[test]
+    fn seeking_back_plays_it_again() {
+        let (replay, recorded, _) = record();
+        let end = replay.end_time();
+        let mut player = ReplayPlayer::new(replay);
+        let mut engine = player.engine();
+
+        player.seek(&mut engine, end);
+        engine.drain_events();
+        player.seek(&mut engine, Duration::ZERO);
+        player.seek(&mut engine, end);
+
+        assert_eq!(engine.drain_events(), recorded);
+    }
+}
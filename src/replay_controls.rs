@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::graphics::Quad;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::language::Language;
+use crate::menu_button::MenuButton;
+use crate::replay::ReplayPlayer;
+
+// Below the buttons, from the bottom edge
+const TIMELINE_BOTTOM: f32 = 6.0;
+const TIMELINE_HEIGHT: f32 = 18.0;
+const TIMELINE_MARGIN: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayAction {
+    TogglePlay,
+    Faster,
+    // Where on the timeline, from 0 to 1
+    Seek(f32),
+    Exit,
+}
+
+// Takes the place of the pause button while watching a replay. The status
+// sits left of the seed, out of the way of the scores.
+pub struct ReplayControls {
+    play: MenuButton,
+    pause: MenuButton,
+    faster: MenuButton,
+    exit: MenuButton,
+    language: Language,
+    screen_width: f32,
+    timeline_y: f32,
+}
+
+impl ReplayControls {
+    pub fn new(
+        language: Language,
+        screen_width: f32,
+        screen_height: f32,
+        bottom_row: f32,
+    ) -> ReplayControls {
+        let play = MenuButton::new(language.tr("Play").to_owned(), 190.0, 70., 20., bottom_row);
+        let pause = MenuButton::new(language.tr("Pause").to_owned(), 190.0, 70., 20., bottom_row);
+        let faster = MenuButton::new(
+            language.tr("Faster").to_owned(),
+            190.0,
+            70.,
+            220.,
+            bottom_row,
+        );
+        let exit = MenuButton::new(
+            language.tr("Exit replay").to_owned(),
+            200.0,
+            70.,
+            screen_width - 240.,
+            bottom_row,
+        );
+
+        ReplayControls {
+            play,
+            pause,
+            faster,
+            exit,
+            language,
+            screen_width,
+            timeline_y: screen_height - TIMELINE_BOTTOM - TIMELINE_HEIGHT,
+        }
+    }
+
+    pub fn clicked(&self, x: f32, y: f32) -> Option<ReplayAction> {
+        if self.play.is_clicked(x, y) {
+            Some(ReplayAction::TogglePlay)
+        } else if self.faster.is_clicked(x, y) {
+            Some(ReplayAction::Faster)
+        } else if self.exit.is_clicked(x, y) {
+            Some(ReplayAction::Exit)
+        } else {
+            self.timeline_at(x, y).map(ReplayAction::Seek)
+        }
+    }
+
+    fn timeline_at(&self, x: f32, y: f32) -> Option<f32> {
+        if !(self.timeline_y..=self.timeline_y + TIMELINE_HEIGHT).contains(&y) {
+            return None;
+        }
+
+        let width = self.screen_width - 2. * TIMELINE_MARGIN;
+        Some(((x - TIMELINE_MARGIN) / width).clamp(0., 1.))
+    }
+
+    pub fn draw(&self, canvas: &mut graphics::Canvas, player: &ReplayPlayer) -> GameResult {
+        if player.is_playing() {
+            self.pause.draw(canvas)?;
+        } else {
+            self.play.draw(canvas)?;
+        }
+        self.faster.draw(canvas)?;
+        self.exit.draw(canvas)?;
+
+        let start = player.replay.start_time();
+        let length = player.replay.end_time().saturating_sub(start);
+        let progress = if length.is_zero() {
+            1.
+        } else {
+            player.time().saturating_sub(start).as_secs_f32() / length.as_secs_f32()
+        };
+
+        let width = self.screen_width - 2. * TIMELINE_MARGIN;
+        canvas.draw(
+            &Quad,
+            DrawParam::default()
+                .color(Color::from_rgb(60, 60, 90))
+                .scale([width, TIMELINE_HEIGHT])
+                .dest([TIMELINE_MARGIN, self.timeline_y]),
+        );
+        canvas.draw(
+            &Quad,
+            DrawParam::default()
+                .color(Color::YELLOW)
+                .scale([width * progress, TIMELINE_HEIGHT])
+                .dest([TIMELINE_MARGIN, self.timeline_y]),
+        );
+
+        let mut text_time = graphics::Text::new(format!(
+            "{} {} / {}  x{}",
+            self.language.tr("Replay"),
+            format_time(player.time()),
+            format_time(player.replay.end_time()),
+            player.speed()
+        ));
+        text_time.set_scale(30.0);
+        canvas.draw(
+            &text_time,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: self.screen_width - 320.0,
+                    y: 40.0,
+                })
+                .offset(Point2 { x: 1.0, y: 0.5 })
+                .color(Color::YELLOW),
+        );
+
+        Ok(())
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,40 @@
+use ggez::{Context, GameResult};
+
+// Every directory under resources with the given manifest, loaded with
+// `load`. Broken ones are left out with a warning that names them as `kind`.
+pub fn discover<T>(
+    ctx: &mut Context,
+    manifest: &str,
+    kind: &str,
+    load: impl Fn(&mut Context, &str) -> GameResult<T>,
+) -> Vec<T> {
+    let mut dirs: Vec<String> = match ctx.fs.read_dir("/") {
+        Ok(entries) => entries
+            .filter_map(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map(str::to_owned)
+            })
+            .collect(),
+        Err(e) => {
+            eprintln!("Could not look for {}s: {}", kind, e);
+            Vec::new()
+        }
+    };
+    dirs.sort();
+    dirs.dedup();
+
+    let mut found = Vec::new();
+    for dir in dirs {
+        if !ctx.fs.is_file(format!("/{}/{}", dir, manifest)) {
+            continue;
+        }
+
+        match load(ctx, &dir) {
+            Ok(resource) => found.push(resource),
+            Err(e) => eprintln!("Skipping {} {}: {}", kind, dir, e),
+        }
+    }
+
+    found
+}
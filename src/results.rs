@@ -0,0 +1,242 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::language::Language;
+use crate::menu_button::MenuButton;
+use crate::players;
+use memory_game::engine::Outcome;
+use memory_game::score::Score;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultsAction {
+    PlayAgain,
+    ChangeLevel,
+    Quit,
+}
+
+pub struct ResultsScreen {
+    pub play_again: MenuButton,
+    pub change_level: MenuButton,
+    pub quit: MenuButton,
+    language: Language,
+    screen_width: f32,
+    screen_height: f32,
+}
+
+impl ResultsScreen {
+    pub fn new(language: Language, screen_width: f32, screen_height: f32) -> ResultsScreen {
+        let start_x = (screen_width - (600. + 100.)) / 2.;
+        let start_y = screen_height - 130.;
+
+        let play_again = MenuButton::new(
+            language.tr("Play again").to_owned(),
+            200.0,
+            70.,
+            start_x,
+            start_y,
+        );
+        let change_level = MenuButton::new(
+            language.tr("Change level").to_owned(),
+            200.0,
+            70.,
+            start_x + 250.,
+            start_y,
+        );
+        let quit = MenuButton::new(
+            language.tr("Quit").to_owned(),
+            200.0,
+            70.,
+            start_x + 500.,
+            start_y,
+        );
+
+        ResultsScreen {
+            play_again,
+            change_level,
+            quit,
+            language,
+            screen_width,
+            screen_height,
+        }
+    }
+
+    pub fn button(&self, action: ResultsAction) -> &MenuButton {
+        match action {
+            ResultsAction::PlayAgain => &self.play_again,
+            ResultsAction::ChangeLevel => &self.change_level,
+            ResultsAction::Quit => &self.quit,
+        }
+    }
+
+    pub fn clicked(&self, x: f32, y: f32) -> Option<ResultsAction> {
+        if self.play_again.is_clicked(x, y) {
+            Some(ResultsAction::PlayAgain)
+        } else if self.change_level.is_clicked(x, y) {
+            Some(ResultsAction::ChangeLevel)
+        } else if self.quit.is_clicked(x, y) {
+            Some(ResultsAction::Quit)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(
+        &self,
+        canvas: &mut graphics::Canvas,
+        outcome: Outcome,
+        score: &Score,
+        players: &[Score],
+        name_entry: Option<&str>,
+    ) -> GameResult {
+        let dest = Point2 {
+            x: self.screen_width / 2.0,
+            y: self.screen_height / 2.0 - 160.0,
+        };
+
+        let tr = |text| self.language.tr(text);
+        let (title, subtitle) = match outcome {
+            Outcome::Lost => (tr("TIME OUT"), tr("You lost the game!")),
+            _ => (tr("FINISH"), tr("You win!")),
+        };
+
+        // In a hot-seat game the best player wins, even when the time ran out
+        let totals: Vec<i64> = players.iter().map(|score| score.total()).collect();
+        let ranking = players::ranking(&totals);
+        let subtitle = match ranking.as_slice() {
+            [] => subtitle.to_owned(),
+            [(_, first), (_, second), ..] if first == second => tr("It's a draw!").to_owned(),
+            [(winner, _), ..] => format!(
+                "{} {}",
+                players::player_name(*winner, self.language),
+                tr("wins!")
+            ),
+        };
+
+        let mut text_title = graphics::Text::new(title);
+
+        let mut text_subtitle = graphics::Text::new(subtitle);
+
+        text_title.set_scale(70.0);
+
+        text_subtitle.set_scale(70.0);
+
+        let draw_params_title = graphics::DrawParam::default()
+            .dest(Point2 {
+                x: dest.x,
+                y: dest.y - 40.0,
+            })
+            .offset(Point2 { x: 0.5, y: 0.5 })
+            .color(Color::WHITE);
+
+        let draw_params_subtitle = graphics::DrawParam::default()
+            .dest(Point2 {
+                x: dest.x,
+                y: dest.y + 40.0,
+            })
+            .offset(Point2 { x: 0.5, y: 0.5 })
+            .color(Color::WHITE);
+
+        canvas.draw(&text_title, draw_params_title);
+
+        canvas.draw(&text_subtitle, draw_params_subtitle);
+
+        if ranking.is_empty() {
+            self.draw_breakdown(canvas, score, dest);
+        } else {
+            self.draw_ranking(canvas, &ranking, dest);
+        }
+
+        // Name for the high score table, typed in on the keyboard
+        if let Some(name) = name_entry {
+            let mut text_name = graphics::Text::new(format!(
+                "{}: {}_  ({})",
+                tr("New high score! Your name"),
+                name,
+                tr("Enter to save")
+            ));
+            text_name.set_scale(30.0);
+
+            canvas.draw(
+                &text_name,
+                graphics::DrawParam::default()
+                    .dest(Point2 {
+                        x: dest.x,
+                        y: self.screen_height - 190.0,
+                    })
+                    .offset(Point2 { x: 0.5, y: 0.5 })
+                    .color(Color::YELLOW),
+            );
+        }
+
+        self.play_again.draw(canvas)?;
+        self.change_level.draw(canvas)?;
+        self.quit.draw(canvas)?;
+
+        Ok(())
+    }
+
+    // Where the points came from
+    fn draw_breakdown(&self, canvas: &mut graphics::Canvas, score: &Score, dest: Point2<f32>) {
+        let tr = |text| self.language.tr(text);
+        let mut text_score = graphics::Text::new(format!(
+            "{}: +{}\n{}: +{}\n{}: +{}\n{}: -{}\n{}: -{}\n\n{}: {}",
+            tr("Matches"),
+            score.matches,
+            tr("Combos"),
+            score.combo,
+            tr("Time left"),
+            score.time,
+            tr("Mistakes"),
+            score.mistakes,
+            tr("Bonuses used"),
+            score.bonuses,
+            tr("Score"),
+            score.total()
+        ));
+        text_score.set_scale(30.0).set_layout(graphics::TextLayout {
+            h_align: graphics::TextAlign::Middle,
+            v_align: graphics::TextAlign::Begin,
+        });
+
+        canvas.draw(
+            &text_score,
+            graphics::DrawParam::default()
+                .dest(Point2 {
+                    x: dest.x,
+                    y: dest.y + 100.0,
+                })
+                .color(Color::WHITE),
+        );
+    }
+
+    fn draw_ranking(
+        &self,
+        canvas: &mut graphics::Canvas,
+        ranking: &[(usize, i64)],
+        dest: Point2<f32>,
+    ) {
+        for (place, (player, total)) in ranking.iter().enumerate() {
+            let mut text_player = graphics::Text::new(format!(
+                "{}. {}  {}",
+                place + 1,
+                players::player_name(*player, self.language),
+                total
+            ));
+            text_player.set_scale(36.0);
+
+            canvas.draw(
+                &text_player,
+                graphics::DrawParam::default()
+                    .dest(Point2 {
+                        x: dest.x,
+                        y: dest.y + 130.0 + 50.0 * place as f32,
+                    })
+                    .offset(Point2 { x: 0.5, y: 0.5 })
+                    .color(players::player_color(*player)),
+            );
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::players::MAX_PLAYERS;
+use memory_game::engine::Engine;
+
+pub const SAVE_GAME_FILE: &str = "save_game.json";
+const SAVE_GAME_VERSION: u32 = 1;
+
+// A game that was left before it was finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    version: u32,
+    pub level: String,
+    // Directory of the deck the game was played with. Saves from before it
+    // was kept go with the deck of the level.
+    #[serde(default)]
+    pub deck: Option<String>,
+    pub engine: Engine,
+}
+
+impl SavedGame {
+    pub fn new(level: String, deck: String, engine: Engine) -> SavedGame {
+        SavedGame {
+            version: SAVE_GAME_VERSION,
+            level,
+            deck: Some(deck),
+            engine,
+        }
+    }
+
+    pub fn path(data_dir: &Path) -> PathBuf {
+        data_dir.join(SAVE_GAME_FILE)
+    }
+
+    // Any problem with the file just means there is nothing to continue
+    pub fn load(path: &Path) -> Option<SavedGame> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                eprintln!("Could not read {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        match SavedGame::parse(&source) {
+            Ok(saved_game) => Some(saved_game),
+            Err(e) => {
+                eprintln!("Ignoring broken save game in {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<SavedGame, String> {
+        let saved_game: SavedGame = serde_json::from_str(source).map_err(|e| e.to_string())?;
+
+        if saved_game.version != SAVE_GAME_VERSION {
+            return Err(format!("unsupported version {}", saved_game.version));
+        }
+        check_engine(&saved_game.engine)?;
+
+        Ok(saved_game)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let source = serde_json::to_string(self)?;
+        fs::write(path, source)
+    }
+
+    pub fn delete(path: &Path) {
+        if let Err(e) = fs::remove_file(path) {
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("Could not delete {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+// A game read from a file has the players and the board the game can show,
+// whatever was written into it
+pub fn check_engine(engine: &Engine) -> Result<(), String> {
+    if engine.players() > MAX_PLAYERS {
+        return Err(format!(
+            "{} players, at most {} can play",
+            engine.players(),
+            MAX_PLAYERS
+        ));
+    }
+    if engine.turn() >= engine.players() {
+        return Err(format!(
+            "turn of player {} of {}",
+            engine.turn() + 1,
+            engine.players()
+        ));
+    }
+
+    let cells = engine
+        .columns()
+        .checked_mul(engine.rows())
+        .ok_or("the board is too big")?;
+    if cells == 0 {
+        return Err("the board is empty".to_owned());
+    }
+    let outside = engine
+        .cards()
+        .find(|((column, row), _)| *column >= engine.columns() || *row >= engine.rows());
+    if let Some((position, _)) = outside {
+        return Err(format!(
+            "card at {:?} is outside the {}x{} board",
+            position,
+            engine.columns(),
+            engine.rows()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use memory_game::engine::{BonusKind, BonusState, Command, Position, Rules};
+    use std::time::Duration;
+
+    // A game in the middle of things: a pair found, a card turned and the
+    // time frozen
+    fn engine() -> Engine {
+        let rules = Rules {
+            duration: Duration::from_secs(60),
+            ..Rules::default()
+        };
+        let mut engine = Engine::new(4, 3, rules, 5);
+        let mut cards: Vec<(Position, u32)> = engine
+            .cards()
+            .map(|(position, card)| (*position, card.match_id))
+            .collect();
+        cards.sort();
+        let first = cards[0];
+        let pair = cards
+            .iter()
+            .find(|(position, id)| *id == first.1 && *position != first.0)
+            .unwrap()
+            .0;
+        let other = cards.iter().find(|(_, id)| *id != first.1).unwrap().0;
+
+        engine.tick(Duration::from_secs(3));
+        engine.apply(Command::FlipCard(first.0));
+        engine.apply(Command::FlipCard(pair));
+        engine.apply(Command::UseBonus(BonusKind::FreezeTime));
+        engine.tick(Duration::from_secs(2));
+        engine.apply(Command::FlipCard(other));
+        engine
+    }
+
+    #[test]
+    fn a_game_survives_a_save_and_load() {
+        let engine = engine();
+        assert_eq!(engine.match_strike(), 1);
+        assert_eq!(engine.selected().len(), 1);
+        assert_eq!(engine.bonus(BonusKind::FreezeTime).state, BonusState::Using);
+
+        let dir = std::env::temp_dir().join(format!("memory_game_save_{}", std::process::id()));
+        let path = SavedGame::path(&dir);
+        SavedGame::new("Easy".to_owned(), "animals".to_owned(), engine.clone())
+            .save(&path)
+            .unwrap();
+        let loaded = SavedGame::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.level, "Easy");
+        assert_eq!(loaded.deck.as_deref(), Some("animals"));
+        assert_eq!(loaded.engine.selected(), engine.selected());
+        assert_eq!(loaded.engine.match_strike(), 1);
+        assert_eq!(loaded.engine.remaining(), engine.remaining());
+        assert_eq!(loaded.engine.played(), engine.played());
+        assert_eq!(loaded.engine.cards().count(), 10);
+        for kind in [
+            BonusKind::BonusTime,
+            BonusKind::FreezeTime,
+            BonusKind::FreeMatch,
+        ] {
+            let (saved, before) = (loaded.engine.bonus(kind), engine.bonus(kind));
+            assert_eq!(saved.state, before.state);
+            assert_eq!(saved.started, before.started);
+        }
+    }
+
+    #[test]
+    fn other_versions_are_not_loaded() {
+        let mut saved_game = SavedGame::new("Easy".to_owned(), "animals".to_owned(), engine());
+        saved_game.version = SAVE_GAME_VERSION + 1;
+        let source = serde_json::to_string(&saved_game).unwrap();
+
+        assert_eq!(
+            SavedGame::parse(&source).unwrap_err(),
+            format!("unsupported version {}", SAVE_GAME_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn broken_files_are_not_loaded() {
+        let saved_game = SavedGame::new("Easy".to_owned(), "animals".to_owned(), engine());
+        let source = serde_json::to_string(&saved_game).unwrap();
+
+        assert!(SavedGame::parse(&source[..source.len() / 2]).is_err());
+        assert!(SavedGame::parse("").is_err());
+        assert!(SavedGame::parse("{\"version\": 1, \"level\": \"Easy\"}").is_err());
+    }
+
+    #[test]
+    fn games_the_board_cannot_show_are_not_loaded() {
+        let mut source = serde_json::to_value(SavedGame::new(
+            "Easy".to_owned(),
+            "animals".to_owned(),
+            engine(),
+        ))
+        .unwrap();
+        source["engine"]["player_scores"] =
+            serde_json::json!(vec![
+                serde_json::to_value(engine().score()).unwrap();
+                MAX_PLAYERS + 1
+            ]);
+        assert_eq!(
+            SavedGame::parse(&source.to_string()).unwrap_err(),
+            format!(
+                "{} players, at most {} can play",
+                MAX_PLAYERS + 1,
+                MAX_PLAYERS
+            )
+        );
+
+        let mut source = serde_json::to_value(SavedGame::new(
+            "Easy".to_owned(),
+            "animals".to_owned(),
+            engine(),
+        ))
+        .unwrap();
+        source["engine"]["columns"] = serde_json::json!(2);
+        assert!(SavedGame::parse(&source.to_string())
+            .unwrap_err()
+            .contains("is outside the 2x3 board"));
+    }
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const POINTS_PER_MATCH: i64 = 100;
+const MAX_MULTIPLIER: i64 = 5;
+const SEEN_MISMATCH_PENALTY: i64 = 25;
+const BONUS_USED_PENALTY: i64 = 50;
+const POINTS_PER_SECOND_LEFT: i64 = 10;
+
+// Points of a single game, kept apart so the results screen can show where
+// they came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub matches: i64,
+    pub combo: i64,
+    pub time: i64,
+    pub mistakes: i64,
+    pub bonuses: i64,
+}
+
+impl Score {
+    pub fn total(&self) -> i64 {
+        (self.matches + self.combo + self.time - self.mistakes - self.bonuses).max(0)
+    }
+
+    // Every match in a row raises the multiplier by one
+    pub fn add_match(&mut self, strike: usize) {
+        let multiplier = (strike as i64).clamp(1, MAX_MULTIPLIER);
+
+        self.matches += POINTS_PER_MATCH;
+        self.combo += POINTS_PER_MATCH * (multiplier - 1);
+    }
+
+    // Only mismatches of cards that were already seen are punished
+    pub fn add_mistake(&mut self) {
+        self.mistakes += SEEN_MISMATCH_PENALTY;
+    }
+
+    pub fn add_bonus_used(&mut self) {
+        self.bonuses += BONUS_USED_PENALTY;
+    }
+
+    pub fn add_time_left(&mut self, remaining: Duration) {
+        self.time += remaining.as_secs() as i64 * POINTS_PER_SECOND_LEFT;
+    }
+}
@@ -0,0 +1,214 @@
+// The game side of the server. It owns the board, checks every flip and
+// tells the clients only about cards that are face up.
+
+use std::io;
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::deck;
+use crate::engine::{Command, Engine, Outcome, Rules};
+use crate::net;
+use crate::net::{ClientMessage, ServerMessage};
+
+const FRAME: Duration = Duration::from_millis(16);
+// How often the clients get the time and the scores
+const STATE_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub players: usize,
+    pub columns: u32,
+    pub rows: u32,
+    pub seconds: u64,
+    pub seed: Option<u64>,
+    pub deck: String,
+    pub race: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            players: 2,
+            columns: 4,
+            rows: 3,
+            seconds: 120,
+            seed: None,
+            deck: deck::DEFAULT_DECK.to_owned(),
+            race: false,
+        }
+    }
+}
+
+// Waits for every player and then plays one game with them
+pub fn serve(listener: &TcpListener, options: &Options) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut clients = Vec::new();
+
+    for player in 0..options.players {
+        let (stream, address) = listener.accept()?;
+        println!("Player {} joined from {}", player + 1, address);
+
+        let mut writer = stream.try_clone()?;
+        net::send(
+            &mut writer,
+            &ServerMessage::Welcome {
+                player,
+                players: options.players,
+            },
+        )?;
+        clients.push(writer);
+
+        listen(player, stream, sender.clone());
+    }
+
+    play(options, &mut clients, receiver);
+
+    Ok(())
+}
+
+// Every client gets its own thread that passes its messages on to the game,
+// `None` once the player is gone
+fn listen(player: usize, stream: TcpStream, sender: Sender<(usize, Option<ClientMessage>)>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+
+        loop {
+            match net::receive(&mut reader) {
+                Ok(Some(message)) => {
+                    if sender.send((player, Some(message))).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    println!("Player {} left", player + 1);
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("Dropping player {}: {}", player + 1, e);
+                    break;
+                }
+            }
+        }
+
+        let _ = sender.send((player, None));
+    });
+}
+
+// A client that is gone just misses the rest of the game
+fn broadcast(clients: &mut [TcpStream], message: &ServerMessage) {
+    for client in clients.iter_mut() {
+        let _ = net::send(client, message);
+    }
+}
+
+fn scores(engine: &Engine) -> Vec<i64> {
+    match engine.player_scores() {
+        [] => vec![engine.score().total()],
+        scores => scores.iter().map(|score| score.total()).collect(),
+    }
+}
+
+fn play(
+    options: &Options,
+    clients: &mut [TcpStream],
+    receiver: Receiver<(usize, Option<ClientMessage>)>,
+) {
+    let rules = Rules {
+        duration: Duration::from_secs(options.seconds),
+        bonuses: Vec::new(),
+        ..Rules::default()
+    };
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let mut engine =
+        Engine::new(options.columns, options.rows, rules, seed).with_players(options.players);
+    println!("Starting the game with seed {}", seed);
+
+    let mut cards: Vec<_> = engine.cards().map(|(position, _)| *position).collect();
+    cards.sort();
+    broadcast(
+        clients,
+        &ServerMessage::Start {
+            columns: options.columns,
+            rows: options.rows,
+            cards,
+            deck: options.deck.clone(),
+            race: options.race,
+        },
+    );
+    broadcast(clients, &ServerMessage::Turn(engine.turn()));
+
+    let mut last_tick = Instant::now();
+    let mut last_state: Option<Instant> = None;
+    let mut connected = vec![true; options.players];
+
+    while engine.outcome() == Outcome::Playing {
+        while let Ok((player, message)) = receiver.try_recv() {
+            match message {
+                None => connected[player] = false,
+                Some(ClientMessage::Flip(position)) => {
+                    let allowed = if options.race {
+                        engine.claim_turn(player)
+                    } else {
+                        engine.turn() == player
+                    };
+
+                    if allowed {
+                        engine.apply(Command::FlipCard(position));
+                    } else {
+                        let _ = net::send(
+                            &mut clients[player],
+                            &ServerMessage::Rejected("It is not your turn".to_owned()),
+                        );
+                    }
+                }
+            }
+        }
+
+        if !connected.contains(&true) {
+            println!("Everybody left");
+            break;
+        }
+
+        let now = Instant::now();
+        engine.tick(now - last_tick);
+        last_tick = now;
+
+        // Nobody waits for a player who left. A pair that is up gets
+        // resolved first, a match would keep the turn.
+        if !options.race && !connected[engine.turn()] && engine.selected().len() < 2 {
+            engine.pass_turn();
+        }
+
+        for event in engine.drain_events() {
+            if let Some(message) = ServerMessage::from_event(&event) {
+                broadcast(clients, &message);
+            }
+        }
+
+        if last_state.is_none_or(|time| now - time >= STATE_INTERVAL) {
+            broadcast(
+                clients,
+                &ServerMessage::State {
+                    remaining: engine.remaining(),
+                    scores: scores(&engine),
+                },
+            );
+            last_state = Some(now);
+        }
+
+        thread::sleep(FRAME);
+    }
+
+    broadcast(
+        clients,
+        &ServerMessage::GameOver {
+            won: engine.outcome() == Outcome::Won,
+            scores: scores(&engine),
+        },
+    );
+    println!("Game over, scores {:?}", scores(&engine));
+}
@@ -0,0 +1,265 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ggez::graphics::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::language::Language;
+use crate::sound_pack::DEFAULT_PACK;
+
+pub const SETTINGS_FILE: &str = "settings.toml";
+const SETTINGS_VERSION: u32 = 1;
+// Volumes go up and down in steps of this many percent
+const VOLUME_STEP: i32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationSpeed {
+    Off,
+    Fast,
+    Normal,
+    Slow,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 4] = [
+        AnimationSpeed::Off,
+        AnimationSpeed::Fast,
+        AnimationSpeed::Normal,
+        AnimationSpeed::Slow,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AnimationSpeed::Off => "Off",
+            AnimationSpeed::Fast => "Fast",
+            AnimationSpeed::Normal => "Normal",
+            AnimationSpeed::Slow => "Slow",
+        }
+    }
+
+    // How long the animations take compared to the normal speed
+    pub fn factor(self) -> f32 {
+        match self {
+            AnimationSpeed::Off => 0.0,
+            AnimationSpeed::Fast => 0.5,
+            AnimationSpeed::Normal => 1.0,
+            AnimationSpeed::Slow => 2.0,
+        }
+    }
+}
+
+// Background of every screen. All of them are dark enough for the white
+// texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColourScheme {
+    Navy,
+    Forest,
+    Plum,
+    Charcoal,
+}
+
+impl ColourScheme {
+    pub const ALL: [ColourScheme; 4] = [
+        ColourScheme::Navy,
+        ColourScheme::Forest,
+        ColourScheme::Plum,
+        ColourScheme::Charcoal,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColourScheme::Navy => "Navy",
+            ColourScheme::Forest => "Forest",
+            ColourScheme::Plum => "Plum",
+            ColourScheme::Charcoal => "Charcoal",
+        }
+    }
+
+    pub fn background(self) -> Color {
+        match self {
+            ColourScheme::Navy => Color::from_rgb(0, 25, 51),
+            ColourScheme::Forest => Color::from_rgb(10, 45, 25),
+            ColourScheme::Plum => Color::from_rgb(50, 15, 50),
+            ColourScheme::Charcoal => Color::from_rgb(25, 25, 25),
+        }
+    }
+}
+
+// Everything picked on the settings screen. Missing entries get their
+// defaults, so older files keep working.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    version: u32,
+    // In percent
+    pub master_volume: u8,
+    pub sfx_volume: u8,
+    pub music_volume: u8,
+    pub music: bool,
+    pub muted: bool,
+    // Directory of the sound pack
+    pub sound_pack: String,
+    // Directory of the deck pack, `None` leaves it to the level
+    pub deck: Option<String>,
+    pub animations: AnimationSpeed,
+    pub colours: ColourScheme,
+    pub fullscreen: bool,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            version: SETTINGS_VERSION,
+            master_volume: 100,
+            sfx_volume: 100,
+            music_volume: 70,
+            music: true,
+            muted: false,
+            sound_pack: DEFAULT_PACK.to_owned(),
+            deck: None,
+            animations: AnimationSpeed::Normal,
+            colours: ColourScheme::Navy,
+            fullscreen: false,
+            language: Language::English,
+        }
+    }
+}
+
+impl Settings {
+    pub fn path(config_dir: &Path) -> PathBuf {
+        config_dir.join(SETTINGS_FILE)
+    }
+
+    // Any problem with the file leaves everything at its default
+    pub fn load(path: &Path) -> Settings {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Settings::default(),
+            Err(e) => {
+                eprintln!("Could not read {}: {}", path.display(), e);
+                return Settings::default();
+            }
+        };
+
+        match Settings::parse(&source) {
+            Ok(settings) => settings,
+            Err(e) => {
+                eprintln!("Ignoring broken settings in {}: {}", path.display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Settings, String> {
+        let mut settings: Settings = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        if settings.version != SETTINGS_VERSION {
+            return Err(format!("unsupported version {}", settings.version));
+        }
+
+        // Edited by hand maybe, nothing goes past full
+        for volume in [
+            &mut settings.master_volume,
+            &mut settings.sfx_volume,
+            &mut settings.music_volume,
+        ] {
+            *volume = (*volume).min(100);
+        }
+
+        Ok(settings)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let source = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, source)
+    }
+}
+
+// One step up or down, going round from full to silent
+pub fn step_volume(volume: u8, step: i32) -> u8 {
+    (volume as i32 + VOLUME_STEP * step).rem_euclid(100 + VOLUME_STEP) as u8
+}
+
+// The next or the previous of `all`, going round at both ends
+pub fn cycle<T: Clone + PartialEq>(all: &[T], current: T, step: i32) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0);
+
+    all[(index as i32 + step).rem_euclid(all.len() as i32) as usize].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_survive_a_save_and_load() {
+        let settings = Settings {
+            master_volume: 80,
+            sfx_volume: 0,
+            music_volume: 30,
+            music: false,
+            muted: true,
+            sound_pack: "retro".to_owned(),
+            deck: Some("animals".to_owned()),
+            animations: AnimationSpeed::Slow,
+            colours: ColourScheme::Plum,
+            fullscreen: true,
+            language: Language::Russian,
+            ..Settings::default()
+        };
+
+        let dir = std::env::temp_dir().join(format!("memory_game_settings_{}", std::process::id()));
+        let path = Settings::path(&dir);
+        settings.save(&path).unwrap();
+        let loaded = Settings::load(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded, settings);
+        assert_eq!(
+            Settings::parse(&toml::to_string(&Settings::default()).unwrap()),
+            Ok(Settings::default())
+        );
+    }
+
+    #[test]
+    fn missing_entries_get_their_defaults() {
+        let settings = Settings::parse("version = 1\nmuted = true").unwrap();
+
+        assert!(settings.muted);
+        assert_eq!(settings.music_volume, Settings::default().music_volume);
+        assert_eq!(settings.deck, None);
+    }
+
+    #[test]
+    fn volumes_stop_at_full() {
+        let settings = Settings::parse(
+            "version = 1\nmaster_volume = 150\nsfx_volume = 255\nmusic_volume = 100",
+        )
+        .unwrap();
+
+        assert_eq!(settings.master_volume, 100);
+        assert_eq!(settings.sfx_volume, 100);
+        assert_eq!(settings.music_volume, 100);
+    }
+
+    #[test]
+    fn other_versions_are_refused() {
+        assert!(Settings::parse("version = 2").is_err());
+        assert!(Settings::parse("master_volume = \"loud\"").is_err());
+    }
+
+    #[test]
+    fn volume_steps_go_round() {
+        assert_eq!(step_volume(90, 1), 100);
+        assert_eq!(step_volume(100, 1), 0);
+        assert_eq!(step_volume(0, -1), 100);
+    }
+}
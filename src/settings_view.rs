@@ -0,0 +1,178 @@
+use ggez::graphics;
+use ggez::graphics::Color;
+use ggez::graphics::DrawParam;
+use ggez::mint::Point2;
+
+use ggez::GameResult;
+
+use crate::language::Language;
+use crate::menu_button::MenuButton;
+
+const ROW_TOP: f32 = 140.0;
+const ROW_HEIGHT: f32 = 78.0;
+// Room for the name left of a button
+const NAME_WIDTH: f32 = 220.0;
+const BUTTON_WIDTH: f32 = 240.0;
+// Rows of the left column, the rest go to the right one
+const LEFT_ROWS: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsItem {
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Music,
+    Mute,
+    SoundPack,
+    Language,
+    Deck,
+    Animations,
+    Colours,
+    Fullscreen,
+    Back,
+}
+
+// Focus order, down the left column and then the right one
+pub const SETTINGS_ITEMS: [SettingsItem; 12] = [
+    SettingsItem::MasterVolume,
+    SettingsItem::SfxVolume,
+    SettingsItem::MusicVolume,
+    SettingsItem::Music,
+    SettingsItem::Mute,
+    SettingsItem::SoundPack,
+    SettingsItem::Language,
+    SettingsItem::Deck,
+    SettingsItem::Animations,
+    SettingsItem::Colours,
+    SettingsItem::Fullscreen,
+    SettingsItem::Back,
+];
+
+impl SettingsItem {
+    pub fn name(self) -> &'static str {
+        match self {
+            SettingsItem::MasterVolume => "Master volume",
+            SettingsItem::SfxVolume => "Sound effects",
+            SettingsItem::MusicVolume => "Music volume",
+            SettingsItem::Music => "Music",
+            SettingsItem::Mute => "Mute",
+            SettingsItem::SoundPack => "Sounds",
+            SettingsItem::Language => "Language",
+            SettingsItem::Deck => "Deck",
+            SettingsItem::Animations => "Animations",
+            SettingsItem::Colours => "Colours",
+            SettingsItem::Fullscreen => "Fullscreen",
+            SettingsItem::Back => "Back",
+        }
+    }
+}
+
+// Every setting is a button that steps through its values, with its name
+// on the left
+pub struct SettingsView {
+    // In the order of `SETTINGS_ITEMS`, without the back button
+    rows: Vec<MenuButton>,
+    back: MenuButton,
+    language: Language,
+    screen_width: f32,
+}
+
+impl SettingsView {
+    pub fn new(language: Language, screen_width: f32, screen_height: f32) -> SettingsView {
+        let mut rows = Vec::new();
+        for index in 0..SETTINGS_ITEMS.len() - 1 {
+            let (x, y) = row_position(index, screen_width);
+            rows.push(MenuButton::new(String::new(), BUTTON_WIDTH, 70., x, y));
+        }
+
+        let back = MenuButton::new(
+            language.tr("Back").to_owned(),
+            200.0,
+            70.,
+            (screen_width - 200.) / 2.,
+            screen_height - 100.,
+        );
+
+        SettingsView {
+            rows,
+            back,
+            language,
+            screen_width,
+        }
+    }
+
+    pub fn set_value(&mut self, item: SettingsItem, value: String) {
+        if let Some(index) = SETTINGS_ITEMS.iter().position(|row| *row == item) {
+            if let Some(button) = self.rows.get_mut(index) {
+                button.set_text(value);
+            }
+        }
+    }
+
+    pub fn button(&self, item: SettingsItem) -> &MenuButton {
+        SETTINGS_ITEMS
+            .iter()
+            .position(|row| *row == item)
+            .and_then(|index| self.rows.get(index))
+            .unwrap_or(&self.back)
+    }
+
+    pub fn clicked(&self, x: f32, y: f32) -> Option<SettingsItem> {
+        SETTINGS_ITEMS
+            .into_iter()
+            .find(|item| self.button(*item).is_clicked(x, y))
+    }
+
+    pub fn draw(&self, canvas: &mut graphics::Canvas) -> GameResult {
+        let mut text_title = graphics::Text::new(self.language.tr("SETTINGS"));
+        text_title.set_scale(60.0);
+        canvas.draw(
+            &text_title,
+            DrawParam::default()
+                .dest(Point2 {
+                    x: self.screen_width / 2.0,
+                    y: 70.0,
+                })
+                .offset(Point2 { x: 0.5, y: 0.5 })
+                .color(Color::WHITE),
+        );
+
+        // Names go to the left of their buttons
+        for (index, button) in self.rows.iter().enumerate() {
+            let rect = button.rect();
+            let mut text_name = graphics::Text::new(self.language.tr(SETTINGS_ITEMS[index].name()));
+            text_name.set_scale(26.0);
+            canvas.draw(
+                &text_name,
+                DrawParam::default()
+                    .dest(Point2 {
+                        x: rect.x - 20.,
+                        y: rect.y + rect.h / 2.,
+                    })
+                    .offset(Point2 { x: 1.0, y: 0.5 })
+                    .color(Color::WHITE),
+            );
+            button.draw(canvas)?;
+        }
+
+        self.back.draw(canvas)?;
+
+        Ok(())
+    }
+}
+
+// Left edge and top of a button. Name and button sit in the middle of
+// their half of the screen.
+fn row_position(index: usize, screen_width: f32) -> (f32, f32) {
+    let (column, row) = if index < LEFT_ROWS {
+        (0., index)
+    } else {
+        (1., index - LEFT_ROWS)
+    };
+    let margin = (screen_width / 2. - (NAME_WIDTH + 20. + BUTTON_WIDTH)) / 2.;
+
+    (
+        screen_width / 2. * column + margin + NAME_WIDTH + 20.,
+        ROW_TOP + ROW_HEIGHT * row as f32,
+    )
+}
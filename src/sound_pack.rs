@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use ggez::{Context, GameError, GameResult};
+use serde::Deserialize;
+
+use crate::audio::SoundEvent;
+use memory_game::resources;
+
+pub const MANIFEST: &str = "sounds.toml";
+pub const DEFAULT_PACK: &str = "sounds";
+const EXTENSIONS: [&str; 3] = ["ogg", "wav", "flac"];
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    sounds: HashMap<String, String>,
+}
+
+// The sounds of the game events from one directory under resources
+#[derive(Debug, Clone)]
+pub struct SoundPack {
+    // Directory name, this is what the settings keep
+    pub dir: String,
+    pub name: String,
+    files: HashMap<SoundEvent, PathBuf>,
+}
+
+impl SoundPack {
+    pub fn parse(dir: &str, source: &str) -> Result<SoundPack, String> {
+        let manifest: Manifest = toml::from_str(source).map_err(|e| e.to_string())?;
+
+        let mut files = HashMap::new();
+        for (key, file) in manifest.sounds {
+            let event = SoundEvent::ALL
+                .into_iter()
+                .find(|event| event.key() == key)
+                .ok_or_else(|| {
+                    format!(
+                        "sound pack \"{}\" has a sound for {}, which is no event",
+                        manifest.name, key
+                    )
+                })?;
+            let playable = Path::new(&file)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| EXTENSIONS.contains(&extension.to_lowercase().as_str()));
+            if !playable {
+                return Err(format!(
+                    "sound pack \"{}\": {} is no ogg, wav or flac file",
+                    manifest.name, file
+                ));
+            }
+
+            files.insert(event, PathBuf::from(format!("/{}/{}", dir, file)));
+        }
+
+        Ok(SoundPack {
+            dir: dir.to_owned(),
+            name: manifest.name,
+            files,
+        })
+    }
+
+    pub fn load(ctx: &mut Context, dir: &str) -> GameResult<SoundPack> {
+        let path = format!("/{}/{}", dir, MANIFEST);
+
+        let mut source = String::new();
+        ctx.fs.open(&path)?.read_to_string(&mut source)?;
+
+        SoundPack::parse(dir, &source)
+            .map_err(|e| GameError::ResourceLoadError(format!("{}: {}", path, e)))
+    }
+
+    pub fn file(&self, event: SoundEvent) -> Option<&Path> {
+        self.files.get(&event).map(PathBuf::as_path)
+    }
+}
+
+pub fn find<'a>(packs: &'a [SoundPack], dir: &str) -> Option<&'a SoundPack> {
+    packs.iter().find(|pack| pack.dir == dir)
+}
+
+// Events the pack leaves out take the sound of the default pack
+pub fn file<'a>(
+    event: SoundEvent,
+    pack: Option<&'a SoundPack>,
+    default: Option<&'a SoundPack>,
+) -> Option<&'a Path> {
+    pack.and_then(|pack| pack.file(event))
+        .or_else(|| default.and_then(|pack| pack.file(event)))
+}
+
+// Every directory under resources with a manifest is a sound pack
+pub fn discover(ctx: &mut Context) -> Vec<SoundPack> {
+    resources::discover(ctx, MANIFEST, "sound pack", SoundPack::load)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: &str = r#"
+        name = "Default"
+
+        [sounds]
+        level_start = "start.ogg"
+        match = "match.ogg"
+        mismatch = "mismatch.ogg"
+        bonus_activated = "bonus.ogg"
+        win = "win.ogg"
+        lose = "lose.ogg"
+    "#;
+
+    #[test]
+    fn missing_sounds_come_from_the_default_pack() {
+        let default = SoundPack::parse(DEFAULT_PACK, DEFAULT).unwrap();
+        let retro = SoundPack::parse(
+            "retro",
+            "name = \"Retro\"\n[sounds]\nmatch = \"beep.WAV\"\nwin = \"tune.flac\"\n",
+        )
+        .unwrap();
+        assert_eq!(retro.name, "Retro");
+
+        let path = |event| file(event, Some(&retro), Some(&default)).map(Path::to_path_buf);
+        assert_eq!(
+            path(SoundEvent::Match),
+            Some(PathBuf::from("/retro/beep.WAV"))
+        );
+        assert_eq!(
+            path(SoundEvent::Win),
+            Some(PathBuf::from("/retro/tune.flac"))
+        );
+        assert_eq!(
+            path(SoundEvent::Lose),
+            Some(PathBuf::from("/sounds/lose.ogg"))
+        );
+
+        // Without a default pack the event stays silent
+        assert_eq!(file(SoundEvent::Lose, Some(&retro), None), None);
+        assert_eq!(
+            file(SoundEvent::Lose, None, Some(&default)),
+            Some(Path::new("/sounds/lose.ogg"))
+        );
+    }
+
+    #[test]
+    fn unknown_events_are_rejected() {
+        assert_eq!(
+            SoundPack::parse(
+                "retro",
+                "name = \"Retro\"\n[sounds]\nshuffle = \"shuffle.ogg\"\n"
+            )
+            .unwrap_err(),
+            "sound pack \"Retro\" has a sound for shuffle, which is no event"
+        );
+    }
+
+    #[test]
+    fn only_ogg_wav_and_flac_files_are_played() {
+        for file in ["beep.mp3", "beep", "ogg"] {
+            let source = format!("name = \"Retro\"\n[sounds]\nmatch = \"{}\"\n", file);
+            assert_eq!(
+                SoundPack::parse("retro", &source).unwrap_err(),
+                format!("sound pack \"Retro\": {} is no ogg, wav or flac file", file)
+            );
+        }
+
+        assert!(SoundPack::parse("retro", "[sounds]\nmatch = \"beep.ogg\"\n").is_err());
+    }
+}
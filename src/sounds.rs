@@ -1,28 +1,45 @@
+use std::collections::HashMap;
+
 use ggez::audio;
-use ggez::{Context, GameResult};
+use ggez::Context;
+
+use crate::audio::SoundEvent;
+use crate::sound_pack;
+use crate::sound_pack::SoundPack;
 
+// One source for every event that has a sound
+#[derive(Default)]
 pub struct Sounds {
-    pub collect: audio::Source,
-    pub fail: audio::Source,
-    pub start: audio::Source,
-    pub wrong: audio::Source,
-    pub bonus: audio::Source,
+    sources: HashMap<SoundEvent, audio::Source>,
 }
 
 impl Sounds {
-    pub fn new(ctx: &mut Context) -> GameResult<Sounds> {
-        let collect = audio::Source::new(ctx, "/sounds/collect.ogg")?;
-        let fail = audio::Source::new(ctx, "/sounds/fail.ogg")?;
-        let start = audio::Source::new(ctx, "/sounds/start.ogg")?;
-        let wrong = audio::Source::new(ctx, "/sounds/wrong.ogg")?;
-        let bonus = audio::Source::new(ctx, "/sounds/bonus.ogg")?;
-
-        Ok(Sounds {
-            collect,
-            fail,
-            start,
-            wrong,
-            bonus,
-        })
+    // A sound that does not load is left out with a warning, the game plays on
+    // without it.
+    pub fn new(ctx: &mut Context, pack: Option<&SoundPack>, default: Option<&SoundPack>) -> Sounds {
+        let mut sources = HashMap::new();
+
+        for event in SoundEvent::ALL {
+            let path = match sound_pack::file(event, pack, default) {
+                Some(path) => path,
+                None => {
+                    eprintln!("There is no sound for {} in the sound packs", event.key());
+                    continue;
+                }
+            };
+
+            match audio::Source::new(ctx, path) {
+                Ok(source) => {
+                    sources.insert(event, source);
+                }
+                Err(e) => eprintln!("Could not load the sound {}: {}", path.display(), e),
+            }
+        }
+
+        Sounds { sources }
+    }
+
+    pub fn source(&mut self, event: SoundEvent) -> Option<&mut audio::Source> {
+        self.sources.get_mut(&event)
     }
 }
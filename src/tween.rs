@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+// How an animation moves from its start (0) to its end (1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1. - (1. - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4. * t * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CardAnimation {
+    // A whole turn, the face changes half way
+    pub flip: Duration,
+    pub flip_easing: Easing,
+    // Fading out and flying to the score after a match
+    pub dismiss: Duration,
+    pub dismiss_easing: Easing,
+}
+
+impl Default for CardAnimation {
+    fn default() -> CardAnimation {
+        CardAnimation {
+            flip: Duration::from_millis(300),
+            flip_easing: Easing::EaseInOut,
+            dismiss: Duration::from_millis(500),
+            dismiss_easing: Easing::EaseIn,
+        }
+    }
+}
+
+impl CardAnimation {
+    // The same animations taking `factor` times as long, 0 turns them off
+    pub fn scaled(self, factor: f32) -> CardAnimation {
+        CardAnimation {
+            flip: self.flip.mul_f32(factor),
+            dismiss: self.dismiss.mul_f32(factor),
+            ..self
+        }
+    }
+}
+
+// Progress of an animation of the given length after `dt` more time, zero
+// length animations end at once
+pub fn advance(progress: f32, dt: Duration, length: Duration) -> f32 {
+    if length.is_zero() {
+        return 1.0;
+    }
+
+    (progress + dt.as_secs_f32() / length.as_secs_f32()).min(1.0)
+}
+
+pub fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 4] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ];
+
+    #[test]
+    fn every_easing_starts_at_0_and_ends_at_1() {
+        for easing in EASINGS {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?}", easing);
+            assert_eq!(easing.apply(-0.5), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.5), 1.0, "{:?}", easing);
+        }
+
+        assert_eq!(Easing::Linear.apply(0.25), 0.25);
+        assert_eq!(Easing::EaseIn.apply(0.5), 0.125);
+        assert_eq!(Easing::EaseOut.apply(0.5), 0.875);
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+    }
+
+    #[test]
+    fn progress_stops_at_the_end() {
+        let length = Duration::from_millis(400);
+
+        assert_eq!(advance(0.0, Duration::from_millis(100), length), 0.25);
+        assert_eq!(advance(0.5, Duration::from_millis(100), length), 0.75);
+        assert_eq!(advance(0.75, Duration::from_millis(300), length), 1.0);
+        assert_eq!(advance(1.0, Duration::from_millis(16), length), 1.0);
+    }
+
+    #[test]
+    fn zero_length_animations_end_at_once() {
+        assert_eq!(advance(0.0, Duration::ZERO, Duration::ZERO), 1.0);
+        assert_eq!(advance(0.0, Duration::from_millis(16), Duration::ZERO), 1.0);
+
+        let off = CardAnimation::default().scaled(0.0);
+        assert_eq!(advance(0.0, Duration::ZERO, off.flip), 1.0);
+        assert_eq!(advance(0.0, Duration::ZERO, off.dismiss), 1.0);
+    }
+
+    #[test]
+    fn lerp_goes_from_one_end_to_the_other() {
+        assert_eq!(lerp(10.0, 20.0, 0.0), 10.0);
+        assert_eq!(lerp(10.0, 20.0, 1.0), 20.0);
+        assert_eq!(lerp(10.0, 20.0, 0.5), 15.0);
+        assert_eq!(lerp(20.0, 10.0, 0.25), 17.5);
+        assert_eq!(
+            lerp(
+                0.0,
+                300.0,
+                Easing::EaseOut.apply(advance(0.0, Duration::from_secs(2), Duration::from_secs(1)))
+            ),
+            300.0
+        );
+    }
+}
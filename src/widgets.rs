@@ -0,0 +1,102 @@
+use crate::bonuses::Bonuses;
+use crate::high_scores_view::HighScoresView;
+use crate::language::Language;
+use crate::layout::Screen;
+use crate::level_config::LevelConfig;
+use crate::levels::Levels;
+use crate::menu_button::MenuButton;
+use crate::pause_menu::PauseMenu;
+use crate::players::MAX_PLAYERS;
+use crate::replay_controls::ReplayControls;
+use crate::results::ResultsScreen;
+use crate::settings_view::SettingsView;
+
+// Everything that has a place on the screen. It is all built again when
+// the window changes size or another language is picked, so texts that
+// depend on the game state are set afterwards.
+pub struct Widgets {
+    pub bonuses: Bonuses,
+    pub levels: Levels,
+    pub pause_menu: PauseMenu,
+    pub results: ResultsScreen,
+    pub high_scores_view: HighScoresView,
+    pub high_scores_button: MenuButton,
+    pub continue_button: MenuButton,
+    pub replay_button: MenuButton,
+    pub replay_controls: ReplayControls,
+    pub players_button: MenuButton,
+    pub seat_buttons: Vec<MenuButton>,
+    pub settings_view: SettingsView,
+    pub settings_button: MenuButton,
+}
+
+impl Widgets {
+    pub fn new(screen: Screen, configs: &[LevelConfig], language: Language) -> Widgets {
+        let width = screen.width;
+        let height = screen.height;
+
+        let bonuses = Bonuses::new(language, width, screen.bottom_row());
+        let levels = Levels::new(configs, width, height);
+        let pause_menu = PauseMenu::new(language, width, height, screen.bottom_row());
+        let results = ResultsScreen::new(language, width, height);
+        let high_scores_view = HighScoresView::new(language, width, height);
+        let replay_controls = ReplayControls::new(language, width, height, screen.bottom_row());
+        let settings_view = SettingsView::new(language, width, height);
+
+        // Bottom row of the home screen, left to right
+        let row_button = |text: &'static str, column: f32| {
+            MenuButton::new(
+                language.tr(text).to_owned(),
+                200.0,
+                70.,
+                (width - 200.) / 2. + 250. * column,
+                height - 150.,
+            )
+        };
+        let settings_button = row_button("Settings", -2.);
+        let players_button = row_button("", -1.);
+        let high_scores_button = row_button("High scores", 0.);
+        let replay_button = row_button("Last replay", 1.);
+
+        // Above the level buttons
+        let levels_top = levels
+            .levels
+            .first()
+            .map_or(height / 2., |level| level.rect().y);
+        let continue_button = MenuButton::new(
+            language.tr("Continue").to_owned(),
+            200.0,
+            70.,
+            (width - 200.) / 2.,
+            levels_top - 110.,
+        );
+
+        let mut seat_buttons = Vec::new();
+        let seats_x = (width - (MAX_PLAYERS as f32 * 250. - 50.)) / 2.;
+        for seat in 0..MAX_PLAYERS {
+            seat_buttons.push(MenuButton::new(
+                String::new(),
+                200.0,
+                70.,
+                seats_x + 250. * seat as f32,
+                height - 250.,
+            ));
+        }
+
+        Widgets {
+            bonuses,
+            levels,
+            pause_menu,
+            results,
+            high_scores_view,
+            high_scores_button,
+            continue_button,
+            replay_button,
+            replay_controls,
+            players_button,
+            seat_buttons,
+            settings_view,
+            settings_button,
+        }
+    }
+}
@@ -0,0 +1,254 @@
+// Plays network games against the server over localhost, in a thread of
+// the test or as the server program of its own
+
+use std::io::BufReader;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use memory_game::engine::{Engine, Position, Rules};
+use memory_game::net;
+use memory_game::net::{ClientMessage, ServerMessage};
+use memory_game::server;
+use memory_game::server::Options;
+
+const SEED: u64 = 7;
+
+struct Client {
+    player: usize,
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+    // Cards this client asked for and the server let it turn
+    flipped: Vec<Position>,
+}
+
+impl Client {
+    // A server program may still be starting up
+    fn connect(address: &str) -> Client {
+        let mut stream = TcpStream::connect(address);
+        for _ in 0..100 {
+            if stream.is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+            stream = TcpStream::connect(address);
+        }
+        let stream = stream.unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let writer = stream.try_clone().unwrap();
+        let mut client = Client {
+            player: 0,
+            writer,
+            reader: BufReader::new(stream),
+            flipped: Vec::new(),
+        };
+
+        match client.next() {
+            ServerMessage::Welcome { player, .. } => client.player = player,
+            message => panic!("expected a welcome, got {:?}", message),
+        }
+        client
+    }
+
+    fn flip(&mut self, position: Position) {
+        net::send(&mut self.writer, &ClientMessage::Flip(position)).unwrap();
+    }
+
+    // The next message that is not about the time and the scores
+    fn next(&mut self) -> ServerMessage {
+        loop {
+            match net::receive(&mut self.reader).unwrap() {
+                Some(ServerMessage::State { .. }) => {}
+                Some(message) => return message,
+                None => panic!("the server closed the connection"),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: ServerMessage) {
+        assert_eq!(self.next(), expected);
+    }
+}
+
+fn start(options: Options) -> (String, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = thread::spawn(move || server::serve(&listener, &options).unwrap());
+
+    (address, server)
+}
+
+// The server program on a free port
+fn start_program() -> (String, std::process::Child) {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let server = Command::new(env!("CARGO_BIN_EXE_server"))
+        .args(["--players", "2", "--columns", "2", "--rows", "2"])
+        .args(["--port", &port.to_string(), "--seed", &SEED.to_string()])
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    (format!("127.0.0.1:{}", port), server)
+}
+
+fn options() -> Options {
+    Options {
+        players: 2,
+        columns: 2,
+        rows: 2,
+        seed: Some(SEED),
+        ..Options::default()
+    }
+}
+
+// The server deals with the same seed, so this is its board
+fn pairs() -> Vec<(Position, Position)> {
+    let engine = Engine::new(2, 2, Rules::default(), SEED);
+    let mut pairs = Vec::new();
+    for match_id in 1..=2 {
+        let mut cards = engine
+            .cards()
+            .filter(|(_, card)| card.match_id == match_id)
+            .map(|(position, _)| *position);
+        pairs.push((cards.next().unwrap(), cards.next().unwrap()));
+    }
+    pairs
+}
+
+fn match_id(position: Position) -> u32 {
+    Engine::new(2, 2, Rules::default(), SEED)
+        .card(position)
+        .unwrap()
+        .match_id
+}
+
+// Every message both clients get on the way to `until`. A face only ever
+// comes with the flip of a card that was asked for.
+fn read_until(clients: &mut [Client], until: &ServerMessage) {
+    let asked: Vec<Position> = clients
+        .iter()
+        .flat_map(|client| client.flipped.clone())
+        .collect();
+
+    for client in clients.iter_mut() {
+        loop {
+            let message = client.next();
+            if let ServerMessage::Flipped {
+                position,
+                match_id: id,
+            } = message
+            {
+                assert!(asked.contains(&position), "{:?} was never turned", position);
+                assert_eq!(id, match_id(position));
+            }
+            if message == *until {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn plays_a_turn_based_game() {
+    let (address, server) = start(options());
+    let mut clients = [Client::connect(&address), Client::connect(&address)];
+    assert_eq!(clients[1].player, 1);
+
+    for client in clients.iter_mut() {
+        match client.next() {
+            ServerMessage::Start { cards, .. } => assert_eq!(cards.len(), 4),
+            message => panic!("expected the start, got {:?}", message),
+        }
+        client.expect(ServerMessage::Turn(0));
+    }
+
+    let [(first_a, first_b), (second_a, second_b)] = pairs()[..] else {
+        unreachable!()
+    };
+
+    // Not the second player's turn yet
+    clients[1].flip(first_a);
+    clients[1].expect(ServerMessage::Rejected("It is not your turn".to_owned()));
+
+    // A mismatch passes the turn on
+    clients[0].flip(first_a);
+    clients[0].flip(second_a);
+    clients[0].flipped = vec![first_a, second_a];
+    read_until(&mut clients, &ServerMessage::Turn(1));
+
+    // Now the first player is the one waiting
+    clients[0].flip(first_b);
+    clients[0].expect(ServerMessage::Rejected("It is not your turn".to_owned()));
+
+    clients[1].flip(first_a);
+    clients[1].flip(first_b);
+    clients[1].flipped = vec![first_a, first_b];
+    read_until(&mut clients, &ServerMessage::Matched(first_a, first_b));
+
+    clients[1].flip(second_a);
+    clients[1].flip(second_b);
+    clients[1].flipped = vec![first_a, first_b, second_a, second_b];
+    read_until(&mut clients, &ServerMessage::Matched(second_a, second_b));
+
+    for client in clients.iter_mut() {
+        match client.next() {
+            ServerMessage::GameOver { won, scores } => {
+                assert!(won);
+                assert!(scores[1] > scores[0]);
+            }
+            message => panic!("expected the end, got {:?}", message),
+        }
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn skips_a_player_who_left() {
+    let (address, server) = start(options());
+    let first = Client::connect(&address);
+    let mut second = Client::connect(&address);
+    drop(first);
+
+    loop {
+        if second.next() == ServerMessage::Turn(1) {
+            break;
+        }
+    }
+
+    drop(second);
+    server.join().unwrap();
+}
+
+#[test]
+fn the_server_program_plays_too() {
+    let (address, mut server) = start_program();
+    let mut clients = [Client::connect(&address), Client::connect(&address)];
+    assert_eq!(clients[1].player, 1);
+
+    for client in clients.iter_mut() {
+        match client.next() {
+            ServerMessage::Start { cards, .. } => assert_eq!(cards.len(), 4),
+            message => panic!("expected the start, got {:?}", message),
+        }
+        client.expect(ServerMessage::Turn(0));
+    }
+
+    // Once the first player leaves the second one is on
+    let [first, mut second] = clients;
+    drop(first);
+    loop {
+        if second.next() == ServerMessage::Turn(1) {
+            break;
+        }
+    }
+
+    drop(second);
+    assert!(server.wait().unwrap().success());
+}